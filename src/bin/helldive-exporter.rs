@@ -0,0 +1,49 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::Parser;
+
+use helldive_rs::exporter::{serve_metrics, ExporterConfig};
+use helldive_rs::{load_registry_file, Language};
+
+/// Serve the state of the Helldivers 2 galactic war as Prometheus metrics
+#[derive(Parser)]
+#[command(name = "helldive-exporter", version, about)]
+struct Args {
+    /// The address to serve /metrics on
+    #[arg(long, default_value = "0.0.0.0:9101")]
+    listen: SocketAddr,
+    /// The ID of the war season to export, the current season if not given
+    #[arg(long)]
+    war_id: Option<i64>,
+    /// The language of the names in the labels, e.g. de, de-DE or German
+    #[arg(long, default_value = "en")]
+    language: Language,
+    /// Seconds between polls of the API
+    #[arg(long, default_value_t = 60)]
+    interval: u64,
+    /// A TOML or JSON file of planet, faction and sector names to add to the bundled tables
+    #[arg(long)]
+    registry: Option<std::path::PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    if let Some(path) = &args.registry {
+        if let Err(e) = load_registry_file(path) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    let config = ExporterConfig {
+        war_id: args.war_id,
+        language: args.language,
+        interval: Duration::from_secs(args.interval.max(1)),
+        on_error: Some(|e| eprintln!("Failed to poll the war status: {}", e)),
+    };
+    if let Err(e) = serve_metrics(args.listen, config).await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
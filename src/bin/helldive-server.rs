@@ -0,0 +1,54 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::Parser;
+
+use helldive_rs::load_registry_file;
+use helldive_rs::server::{serve, ServerConfig};
+
+/// Serve the Helldivers 2 API with names filled in, sharing one cached upstream client between all consumers
+#[derive(Parser)]
+#[command(name = "helldive-server", version, about)]
+struct Args {
+    /// The address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+    /// Seconds a response from the API is served from the cache
+    #[arg(long, default_value_t = 20)]
+    cache_ttl: u64,
+    /// The most requests to the API per minute
+    #[arg(long, default_value_t = 30)]
+    max_upstream_per_minute: usize,
+    /// The most requests per minute from one client address
+    #[arg(long, default_value_t = 120)]
+    max_requests_per_client: usize,
+    /// Seconds between polls of the current war for /events, 0 to not push events
+    #[arg(long, default_value_t = 60)]
+    watch_interval: u64,
+    /// A TOML or JSON file of planet, faction and sector names to add to the bundled tables
+    #[arg(long)]
+    registry: Option<std::path::PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    if let Some(path) = &args.registry {
+        if let Err(e) = load_registry_file(path) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    let config = ServerConfig {
+        cache_ttl: Duration::from_secs(args.cache_ttl),
+        max_upstream_per_minute: args.max_upstream_per_minute,
+        max_requests_per_client_per_minute: args.max_requests_per_client,
+        watch_interval: (args.watch_interval > 0).then(|| Duration::from_secs(args.watch_interval)),
+        on_watch_error: Some(|e| eprintln!("Failed to poll the current war: {}", e)),
+        ..ServerConfig::default()
+    };
+    if let Err(e) = serve(args.listen, config).await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+use helldive_rs::{
+    current_war_id, get_campaigns, get_faction_distribution, get_localized_faction_name, get_planet, news_since, NewsCursor, get_sector_summaries, get_status,
+    get_top_planets_by_player_count, get_total_player_count, get_war_info, get_war_time, find_planet,
+    find_planets, load_registry_file, reconcile_resources, Language, PlanetInfo, WarInfo,
+};
+
+/// Query the Helldivers 2 galactic war from the terminal
+#[derive(Parser)]
+#[command(name = "helldive", version, about)]
+struct Cli {
+    /// The ID of the war season to query, the current season if not given
+    #[arg(long, global = true)]
+    war_id: Option<i64>,
+    /// The language of in-game text and names, e.g. de, de-DE or German
+    #[arg(long, global = true, default_value = "en")]
+    language: Language,
+    /// Print JSON instead of a table
+    #[arg(long, global = true)]
+    json: bool,
+    /// A TOML or JSON file of planet, faction and sector names to add to the bundled tables
+    #[arg(long, global = true)]
+    registry: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show an overview of the war
+    Status,
+    /// List planets by player count
+    Planets {
+        /// The number of planets to show
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Show a single planet by name or index
+    Planet {
+        /// The name or index of the planet
+        planet: String,
+    },
+    /// Show the news feed
+    News {
+        /// Only show news published after this war time
+        #[arg(long)]
+        since: Option<i64>,
+    },
+    /// List the sectors and who controls them
+    Sectors,
+    /// List the active campaigns
+    Campaigns,
+    /// Show the current war time
+    WarTime,
+    /// Compare the planet and sector tables with the WarInfo, and write updated tables
+    Reconcile {
+        /// Read the WarInfo from a JSON file instead of the API
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Write the updated planets.toml and sectors.toml to this directory
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli).await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = &cli.registry {
+        load_registry_file(path)?;
+    }
+    // reconciling a saved WarInfo does not talk to the API, so it resolves the war ID itself
+    if let Command::Reconcile { file, out } = cli.command {
+        return reconcile(cli.war_id, file, out, cli.json).await;
+    }
+    let war_id = match cli.war_id {
+        Some(war_id) => war_id,
+        None => current_war_id().await?,
+    };
+    match cli.command {
+        Command::Status => {
+            let status = get_status(war_id, cli.language).await?;
+            if cli.json {
+                return print_json(&status);
+            }
+            println!("War {} at war time {}", status.war_id, status.time);
+            println!("Impact multiplier: {}", status.impact_multiplier);
+            println!("Players: {}", get_total_player_count(&status));
+            let mut distribution: Vec<(i64, i64)> = get_faction_distribution(&status).into_iter().collect();
+            distribution.sort();
+            for (faction, planets) in distribution {
+                println!("{:<12} {} planets", faction_name(faction, cli.language), planets);
+            }
+            for event in &status.global_events {
+                println!("\n{}\n{}", event.title_markup().to_ansi(), event.message_markup().to_ansi());
+            }
+        }
+        Command::Planets { top } => {
+            let status = get_status(war_id, cli.language).await?;
+            let war_info = get_war_info(war_id).await?;
+            let planets = get_top_planets_by_player_count(&status, top);
+            if cli.json {
+                let planets: Vec<_> = planets.into_iter().map(|(ps, _)| ps).collect();
+                return print_json(&planets);
+            }
+            let max_health = max_health(&war_info.planet_infos);
+            println!("{:>5}  {:<24} {:<12} {:>8} {:>8}", "INDEX", "NAME", "OWNER", "PLAYERS", "HEALTH");
+            for (ps, players) in planets {
+                println!("{:>5}  {:<24} {:<12} {:>8} {:>7.2}%", ps.index, ps.planet_name, faction_name(ps.owner, cli.language), players, percent(ps.health, max_health.get(&ps.index)));
+            }
+        }
+        Command::Planet { planet } => {
+            // indices missing from the registry are still valid, e.g. planets added mid-season
+            let Some(index) = find_planet(&planet).or_else(|| planet.trim().parse().ok()) else {
+                let suggestions: Vec<String> = find_planets(&planet, 3).into_iter().map(|m| m.name).collect();
+                return Err(format!("Unknown planet: {}. Did you mean: {}?", planet, suggestions.join(", ")).into());
+            };
+            let status = get_status(war_id, cli.language).await?;
+            let war_info = get_war_info(war_id).await?;
+            let ps = status.planet_status.iter().find(|ps| ps.index == index);
+            let pi = war_info.planet_infos.iter().find(|pi| pi.index == index);
+            let campaigns: Vec<_> = status.campaigns.iter().filter(|c| c.planet_index == index).collect();
+            let attacks: Vec<_> = status.planet_attacks.iter().filter(|pa| pa.source == index || pa.target == index).collect();
+            if cli.json {
+                return print_json(&serde_json::json!({
+                    "planet": get_planet(index), "status": ps, "info": pi, "campaigns": campaigns, "attacks": attacks,
+                }));
+            }
+            let details = get_planet(index).unwrap_or_default();
+            println!("{} ({})", details.localized_name(cli.language), index);
+            if let Some(description) = &details.description {
+                println!("{}", description);
+            }
+            if let Some(pi) = pi {
+                println!("Sector:  {}", helldive_rs::get_localized_sector_name(pi.sector, cli.language).unwrap_or_default());
+            }
+            if let Some(biome) = &details.biome {
+                println!("Biome:   {} - {}", biome.name, biome.description);
+            }
+            for hazard in &details.hazards {
+                println!("Hazard:  {} - {}", hazard.name, hazard.description);
+            }
+            if let Some(ps) = ps {
+                println!("Owner:   {}", faction_name(ps.owner, cli.language));
+                println!("Players: {}", ps.players);
+                println!("Health:  {:.2}% ({:+}/s)", percent(ps.health, pi.map(|pi| &pi.max_health)), ps.regen_per_second);
+            }
+            for campaign in campaigns {
+                println!("Campaign {} (type {})", campaign.id, campaign.r#type);
+            }
+            for attack in attacks {
+                println!("Attack {} -> {}", attack.source_name, attack.target_name);
+            }
+        }
+        Command::News { since } => {
+            // items published at `since` were already seen, whatever their ID
+            let mut cursor = since.map_or(NewsCursor::default(), |since| NewsCursor { published: since, id: i64::MAX });
+            let news = news_since(war_id, cli.language, &mut cursor).await?;
+            if cli.json {
+                return print_json(&news);
+            }
+            for item in news {
+                println!("[{}] [{:?}] {}\n", item.published, item.category(cli.language), item.markup().to_ansi().trim());
+            }
+        }
+        Command::Sectors => {
+            let status = get_status(war_id, cli.language).await?;
+            let war_info = get_war_info(war_id).await?;
+            let summaries = get_sector_summaries(&war_info, &status);
+            if cli.json {
+                return print_json(&summaries);
+            }
+            println!("{:>4}  {:<16} {:>7} {:>9} {:>8}  OWNERS", "ID", "NAME", "PLANETS", "CONTESTED", "PLAYERS");
+            for summary in summaries {
+                let mut owners: Vec<(i64, i64)> = summary.owners.into_iter().collect();
+                owners.sort();
+                let owners: Vec<String> = owners.into_iter().map(|(f, n)| format!("{} {}", faction_name(f, cli.language), n)).collect();
+                println!("{:>4}  {:<16} {:>7} {:>9} {:>8}  {}", summary.id, summary.name, summary.planets.len(), summary.contested_planets.len(), summary.players, owners.join(", "));
+            }
+        }
+        Command::Campaigns => {
+            let status = get_status(war_id, cli.language).await?;
+            let war_info = get_war_info(war_id).await?;
+            let campaigns = get_campaigns(&war_info, &status);
+            if cli.json {
+                return print_json(&campaigns);
+            }
+            println!("{:>6}  {:<24} {:<10} {:<12} {:>8} {:>9}", "ID", "PLANET", "TYPE", "ENEMY", "PLAYERS", "PROGRESS");
+            for campaign in &campaigns {
+                println!(
+                    "{:>6}  {:<24} {:<10} {:<12} {:>8} {:>8.1}%",
+                    campaign.campaign.id,
+                    campaign.campaign.planet_name,
+                    campaign.campaign_type.name(),
+                    campaign.race.map(|race| faction_name(race, cli.language)).unwrap_or_default(),
+                    campaign.status.map(|ps| ps.players).unwrap_or_default(),
+                    campaign.progress().unwrap_or_default() * 100.0,
+                );
+            }
+        }
+        Command::WarTime => {
+            let time = get_war_time(war_id).await?;
+            if cli.json {
+                return print_json(&serde_json::json!({ "time": time }));
+            }
+            println!("{}", time);
+        }
+        Command::Reconcile { .. } => unreachable!("reconcile is handled before the war ID is resolved"),
+    }
+    Ok(())
+}
+
+/// Compare the planet and sector tables with a WarInfo from a file or the API
+async fn reconcile(war_id: Option<i64>, file: Option<PathBuf>, out: Option<PathBuf>, json: bool) -> Result<(), Box<dyn Error>> {
+    let war_info: WarInfo = match file {
+        Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        None => get_war_info(match war_id {
+            Some(war_id) => war_id,
+            None => current_war_id().await?,
+        }).await?,
+    };
+    let update = reconcile_resources(&war_info);
+    if let Some(dir) = out {
+        std::fs::write(dir.join("planets.toml"), &update.planets_toml)?;
+        std::fs::write(dir.join("sectors.toml"), &update.sectors_toml)?;
+    }
+    if json {
+        return print_json(&update);
+    }
+    if update.is_empty() {
+        println!("The planet and sector tables match the WarInfo");
+    }
+    for planet in &update.unnamed_planets {
+        println!("Planet {} has no name", planet);
+    }
+    for sector in &update.new_sectors {
+        println!("Sector {} has no name", sector);
+    }
+    for renumbering in &update.renumbered_sectors {
+        println!("Sector {} moved from ID {} to {}", renumbering.name, renumbering.from, renumbering.to);
+    }
+    for reassignment in &update.sector_reassignments {
+        let from = reassignment.from.map_or("no sector".to_string(), |id| format!("sector {}", id));
+        println!("Planet {} moved from {} to sector {}", reassignment.planet, from, reassignment.to);
+    }
+    Ok(())
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn faction_name(id: i64, language: Language) -> String {
+    get_localized_faction_name(id, language).unwrap_or_else(|| id.to_string())
+}
+
+fn max_health(planet_infos: &[PlanetInfo]) -> HashMap<i64, i64> {
+    planet_infos.iter().map(|pi| (pi.index, pi.max_health)).collect()
+}
+
+fn percent(health: i64, max_health: Option<&i64>) -> f64 {
+    match max_health {
+        Some(&max) if max > 0 => health as f64 / max as f64 * 100.0,
+        _ => 0.0,
+    }
+}
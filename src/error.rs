@@ -9,6 +9,12 @@ pub enum HelldiversError {
     JsonError(serde_json::Error),
     APIError(String),
     InvalidWarId(String),
+    RenderError(String),
+    ResourceError(String),
+    InvalidLanguage(String),
+    StorageError(String),
+    ExportError(String),
+    ServerError(String),
 }
 
 impl fmt::Display for HelldiversError {
@@ -18,6 +24,12 @@ impl fmt::Display for HelldiversError {
             HelldiversError::JsonError(err) => write!(f, "JSON deserialization error: {}", err),
             HelldiversError::InvalidWarId(message) => write!(f, "Invalid war ID: {}", message),
             HelldiversError::APIError(message) => write!(f, "API error: {}", message),
+            HelldiversError::RenderError(message) => write!(f, "Render error: {}", message),
+            HelldiversError::ResourceError(message) => write!(f, "Resource error: {}", message),
+            HelldiversError::InvalidLanguage(language) => write!(f, "Unsupported language: {}", language),
+            HelldiversError::StorageError(message) => write!(f, "Storage error: {}", message),
+            HelldiversError::ExportError(message) => write!(f, "Export error: {}", message),
+            HelldiversError::ServerError(message) => write!(f, "Server error: {}", message),
         }
     }
 }
@@ -29,6 +41,12 @@ impl Error for HelldiversError {
             HelldiversError::JsonError(err) => Some(err),
             HelldiversError::InvalidWarId(_) => None,
             HelldiversError::APIError(_) => None,
+            HelldiversError::RenderError(_) => None,
+            HelldiversError::ResourceError(_) => None,
+            HelldiversError::InvalidLanguage(_) => None,
+            HelldiversError::StorageError(_) => None,
+            HelldiversError::ExportError(_) => None,
+            HelldiversError::ServerError(_) => None,
         }
     }
 }
@@ -45,6 +63,13 @@ impl From<serde_json::Error> for HelldiversError {
     }
 }
 
+#[cfg(feature = "storage")]
+impl From<rusqlite::Error> for HelldiversError {
+    fn from(err: rusqlite::Error) -> Self {
+        HelldiversError::StorageError(err.to_string())
+    }
+}
+
 impl From<Response> for HelldiversError {
     fn from(response: Response) -> Self {
         if response.status() == StatusCode::BAD_REQUEST {
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+#[cfg(feature = "csv")]
+use std::io::Write;
+
+use serde::Serialize;
+
+#[cfg(feature = "csv")]
+use crate::error::HelldiversError;
+use crate::models::api::{PlanetInfo, Status, WarInfo};
+use crate::models::HUMAN_FACTION_ID;
+use crate::registry;
+
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "parquet")]
+pub use self::parquet::{planet_rows_to_record_batch, write_parquet};
+
+/// One planet in one sample, the row of a tidy time-series table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanetRow {
+    pub war_id: i64,
+    pub war_time: i64,
+    /// When the sample was recorded, in seconds since the Unix epoch, if known
+    pub recorded_at: Option<u64>,
+    pub planet_index: i64,
+    pub planet_name: String,
+    pub sector: Option<i64>,
+    pub owner: i64,
+    pub owner_name: String,
+    pub health: i64,
+    pub max_health: Option<i64>,
+    /// 100 for planets owned by humans, otherwise the share of health the planet has lost, in percent
+    pub liberation: Option<f64>,
+    pub players: i64,
+    pub regen_per_second: f64,
+}
+
+/// Turn a sequence of Status samples into one row per planet per sample
+///
+/// Arguments:
+///   samples: impl IntoIterator<Item = (&Status, Option<u64>)> - Each Status with the Unix time it was fetched at, if known
+///   war_info: &WarInfo - The WarInfo to get the sectors and maximum planet health from
+pub fn planet_rows<'a>(samples: impl IntoIterator<Item = (&'a Status, Option<u64>)>, war_info: &WarInfo) -> Vec<PlanetRow> {
+    let infos: HashMap<i64, &PlanetInfo> = war_info.planet_infos.iter().map(|pi| (pi.index, pi)).collect();
+    let registry = registry();
+    let mut rows = Vec::new();
+    for (status, recorded_at) in samples {
+        for ps in &status.planet_status {
+            let info = infos.get(&ps.index);
+            let max_health = info.map(|pi| pi.max_health);
+            rows.push(PlanetRow {
+                war_id: status.war_id,
+                war_time: status.time,
+                recorded_at,
+                planet_index: ps.index,
+                planet_name: registry.planet_name(ps.index).unwrap_or_default(),
+                sector: info.map(|pi| pi.sector),
+                owner: ps.owner,
+                owner_name: registry.faction_name(ps.owner).unwrap_or_default(),
+                health: ps.health,
+                max_health,
+                liberation: liberation(ps.owner, ps.health, max_health),
+                players: ps.players,
+                regen_per_second: ps.regen_per_second,
+            });
+        }
+    }
+    rows
+}
+
+pub(crate) fn liberation(owner: i64, health: i64, max_health: Option<i64>) -> Option<f64> {
+    if owner == HUMAN_FACTION_ID {
+        return Some(100.0);
+    }
+    max_health.filter(|max| *max > 0).map(|max| 100.0 * (1.0 - health as f64 / max as f64))
+}
+
+/// Write planet rows as CSV with a header row
+///
+/// Arguments:
+///   rows: &[PlanetRow] - The rows to write
+///   writer: impl Write - Where to write the CSV, e.g. a file
+#[cfg(feature = "csv")]
+pub fn write_csv(rows: &[PlanetRow], writer: impl Write) -> Result<(), HelldiversError> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for row in rows {
+        writer.serialize(row).map_err(|e| HelldiversError::ExportError(e.to_string()))?;
+    }
+    writer.flush().map_err(|e| HelldiversError::ExportError(e.to_string()))
+}
@@ -0,0 +1,63 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+
+use crate::error::HelldiversError;
+use crate::export::PlanetRow;
+
+fn schema() -> Schema {
+    Schema::new(vec![
+        Field::new("war_id", DataType::Int64, false),
+        Field::new("war_time", DataType::Int64, false),
+        Field::new("recorded_at", DataType::UInt64, true),
+        Field::new("planet_index", DataType::Int64, false),
+        Field::new("planet_name", DataType::Utf8, false),
+        Field::new("sector", DataType::Int64, true),
+        Field::new("owner", DataType::Int64, false),
+        Field::new("owner_name", DataType::Utf8, false),
+        Field::new("health", DataType::Int64, false),
+        Field::new("max_health", DataType::Int64, true),
+        Field::new("liberation", DataType::Float64, true),
+        Field::new("players", DataType::Int64, false),
+        Field::new("regen_per_second", DataType::Float64, false),
+    ])
+}
+
+/// Convert planet rows to an Arrow record batch, with the columns of `PlanetRow`
+///
+/// Arguments:
+///   rows: &[PlanetRow] - The rows to convert
+pub fn planet_rows_to_record_batch(rows: &[PlanetRow]) -> Result<RecordBatch, HelldiversError> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(rows.iter().map(|r| r.war_id).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| r.war_time).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| r.recorded_at).collect::<UInt64Array>()),
+        Arc::new(rows.iter().map(|r| r.planet_index).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| Some(r.planet_name.as_str())).collect::<StringArray>()),
+        Arc::new(rows.iter().map(|r| r.sector).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| r.owner).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| Some(r.owner_name.as_str())).collect::<StringArray>()),
+        Arc::new(rows.iter().map(|r| r.health).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| r.max_health).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| r.liberation).collect::<Float64Array>()),
+        Arc::new(rows.iter().map(|r| r.players).collect::<Int64Array>()),
+        Arc::new(rows.iter().map(|r| r.regen_per_second).collect::<Float64Array>()),
+    ];
+    RecordBatch::try_new(Arc::new(schema()), columns).map_err(|e| HelldiversError::ExportError(e.to_string()))
+}
+
+/// Write planet rows as a Parquet file
+///
+/// Arguments:
+///   rows: &[PlanetRow] - The rows to write
+///   writer: impl Write + Send - Where to write the Parquet file
+pub fn write_parquet(rows: &[PlanetRow], writer: impl Write + Send) -> Result<(), HelldiversError> {
+    let batch = planet_rows_to_record_batch(rows)?;
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None).map_err(|e| HelldiversError::ExportError(e.to_string()))?;
+    writer.write(&batch).map_err(|e| HelldiversError::ExportError(e.to_string()))?;
+    writer.close().map_err(|e| HelldiversError::ExportError(e.to_string()))?;
+    Ok(())
+}
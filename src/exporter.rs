@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+
+use crate::error::HelldiversError;
+use crate::export::liberation;
+use crate::models::api::{PlanetInfo, Status, WarInfo};
+use crate::models::Language;
+use crate::{current_war_id, get_faction_distribution, get_status, get_total_player_count, get_war_info, registry};
+
+/// The content type of the Prometheus text format
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How the exporter polls the API
+#[derive(Debug, Clone)]
+pub struct ExporterConfig {
+    /// The war to poll, the current war season if not given
+    pub war_id: Option<i64>,
+    /// The language of the planet, sector and faction names in the labels
+    pub language: Language,
+    /// The time between polls of the Status
+    pub interval: Duration,
+    /// Called with the error of each failed poll, e.g. to log it
+    pub on_error: Option<fn(&HelldiversError)>,
+}
+
+impl Default for ExporterConfig {
+    fn default() -> Self {
+        ExporterConfig { war_id: None, language: Language::English, interval: Duration::from_secs(60), on_error: None }
+    }
+}
+
+/// Render the state of a war in the Prometheus text format
+///
+/// Planet gauges are labelled with the planet index, name and sector, faction gauges with the faction ID and name. The
+/// owner of a planet is its own gauge rather than a label, so the series of a planet continue when it changes hands.
+///
+/// Arguments:
+///   status: &Status - The Status to get the planet and faction state from
+///   war_info: &WarInfo - The WarInfo to get the sectors and maximum planet health from
+///   language: Language - The language of the names in the labels
+pub fn render_metrics(status: &Status, war_info: &WarInfo, language: Language) -> String {
+    let infos: HashMap<i64, &PlanetInfo> = war_info.planet_infos.iter().map(|pi| (pi.index, pi)).collect();
+    let registry = registry();
+    let faction_name = |id: i64| registry.localized_faction_name(id, language).unwrap_or_else(|| id.to_string());
+
+    let mut planets = Vec::new();
+    for ps in &status.planet_status {
+        let info = infos.get(&ps.index);
+        let sector = info.map(|pi| pi.sector);
+        let labels = format!(
+            r#"war="{}",planet="{}",name="{}",sector="{}""#,
+            status.war_id,
+            ps.index,
+            escape(&registry.localized_planet_name(ps.index, language).unwrap_or_default()),
+            escape(&sector.and_then(|s| registry.localized_sector_name(s, language)).unwrap_or_default()),
+        );
+        planets.push((labels, ps, liberation(ps.owner, ps.health, info.map(|pi| pi.max_health))));
+    }
+
+    // writing to a String never fails, so the results of writeln! are ignored
+    let mut out = String::new();
+    let mut gauge = |name: &str, help: &str, samples: Vec<(String, f64)>| {
+        let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+        for (labels, value) in samples {
+            if labels.is_empty() {
+                let _ = writeln!(out, "{} {}", name, value);
+            } else {
+                let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+            }
+        }
+    };
+
+    let war = format!(r#"war="{}""#, status.war_id);
+    gauge("helldivers_players_total", "Players in the war", vec![(war.clone(), get_total_player_count(status) as f64)]);
+    gauge("helldivers_impact_multiplier", "Impact multiplier of the war", vec![(war.clone(), status.impact_multiplier)]);
+    gauge("helldivers_war_time", "War time of the Status", vec![(war.clone(), status.time as f64)]);
+    gauge("helldivers_planet_players", "Players on a planet", planets.iter().map(|(l, ps, _)| (l.clone(), ps.players as f64)).collect());
+    gauge("helldivers_planet_health", "Health of a planet", planets.iter().map(|(l, ps, _)| (l.clone(), ps.health as f64)).collect());
+    gauge("helldivers_planet_owner", "Faction ID of the owner of a planet", planets.iter().map(|(l, ps, _)| (l.clone(), ps.owner as f64)).collect());
+    gauge(
+        "helldivers_planet_liberation_percent",
+        "Liberation of a planet in percent, 100 for planets owned by humans",
+        planets.iter().filter_map(|(l, _, liberation)| liberation.map(|value| (l.clone(), value))).collect(),
+    );
+    gauge(
+        "helldivers_planet_regen_per_second",
+        "Health a planet regenerates per second",
+        planets.iter().map(|(l, ps, _)| (l.clone(), ps.regen_per_second)).collect(),
+    );
+
+    let mut factions: Vec<(i64, i64)> = get_faction_distribution(status).into_iter().collect();
+    factions.sort();
+    gauge(
+        "helldivers_faction_planets",
+        "Planets owned by a faction",
+        factions.into_iter()
+            .map(|(id, count)| (format!(r#"war="{}",faction="{}",name="{}""#, status.war_id, id, escape(&faction_name(id))), count as f64))
+            .collect(),
+    );
+    out
+}
+
+/// Escape a Prometheus label value
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// The latest metrics, and whether the latest poll succeeded
+#[derive(Default)]
+struct Scrape {
+    metrics: String,
+    up: bool,
+    last_success: u64,
+}
+
+/// Poll the API and serve the metrics at `/metrics` until the server fails
+///
+/// The Status is polled every `config.interval`, and the WarInfo whenever the war changes. Failed polls keep the
+/// previous metrics, set `helldivers_up` to 0 and are passed to `config.on_error`.
+///
+/// Arguments:
+///   addr: SocketAddr - The address to listen on, e.g. `0.0.0.0:9101`
+///   config: ExporterConfig - The war, language and poll interval
+pub async fn serve_metrics(addr: SocketAddr, config: ExporterConfig) -> Result<(), HelldiversError> {
+    let scrape = Arc::new(RwLock::new(Scrape::default()));
+
+    let poller = scrape.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.interval);
+        let mut war_info: Option<WarInfo> = None;
+        loop {
+            interval.tick().await;
+            let result = poll(&config, &mut war_info).await;
+            let mut scrape = poller.write().unwrap_or_else(|e| e.into_inner());
+            match result {
+                Ok(metrics) => {
+                    scrape.metrics = metrics;
+                    scrape.up = true;
+                    scrape.last_success = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+                }
+                Err(e) => {
+                    if let Some(on_error) = config.on_error {
+                        on_error(&e);
+                    }
+                    scrape.up = false;
+                }
+            }
+        }
+    });
+
+    let app = Router::new().route("/metrics", get(move || {
+        let scrape = scrape.clone();
+        async move {
+            let scrape = scrape.read().unwrap_or_else(|e| e.into_inner());
+            let body = format!(
+                "{}# HELP helldivers_up Whether the latest poll of the API succeeded\n# TYPE helldivers_up gauge\nhelldivers_up {}\n\
+                 # HELP helldivers_last_success_timestamp_seconds Unix time of the latest successful poll\n\
+                 # TYPE helldivers_last_success_timestamp_seconds gauge\nhelldivers_last_success_timestamp_seconds {}\n",
+                scrape.metrics, u8::from(scrape.up), scrape.last_success,
+            );
+            ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], body).into_response()
+        }
+    }));
+
+    let listener = tokio::net::TcpListener::bind(addr).await
+        .map_err(|e| HelldiversError::ServerError(format!("{}: {}", addr, e)))?;
+    axum::serve(listener, app).await.map_err(|e| HelldiversError::ServerError(e.to_string()))
+}
+
+async fn poll(config: &ExporterConfig, war_info: &mut Option<WarInfo>) -> Result<String, HelldiversError> {
+    let war_id = match config.war_id {
+        Some(war_id) => war_id,
+        None => current_war_id().await?,
+    };
+    let status = get_status(war_id, config.language).await?;
+    let info = match war_info.take() {
+        Some(info) if info.war_id == war_id => info,
+        _ => get_war_info(war_id).await?,
+    };
+    let metrics = render_metrics(&status, &info, config.language);
+    *war_info = Some(info);
+    Ok(metrics)
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::markup::Markup;
+use crate::models::api::{NewsItem, Status, WarInfo};
+use crate::models::Language;
+
+/// The number of characters of a message used as the title of a news entry
+const TITLE_LENGTH: usize = 80;
+
+/// Converts war time, the seconds since the start of a war, to Unix time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarClock {
+    /// The Unix time at war time 0
+    pub start: i64,
+}
+
+impl WarClock {
+    /// A clock starting at the start date of a war
+    pub fn from_war_info(war_info: &WarInfo) -> WarClock {
+        WarClock { start: war_info.start_date }
+    }
+
+    /// A clock matching a war time observed at a Unix time, e.g. from `get_war_time` and the system clock
+    ///
+    /// This follows the game clock more closely than the start date if the war was ever paused.
+    pub fn from_war_time(war_time: i64, now: i64) -> WarClock {
+        WarClock { start: now - war_time }
+    }
+
+    /// The Unix time of a war time
+    pub fn unix(&self, war_time: i64) -> i64 {
+        self.start + war_time
+    }
+}
+
+/// The war time each global event was first seen at, to date global events in feeds
+///
+/// Global events have no publication time, so they keep the date they were first seen at between fetches.
+#[derive(Debug, Clone, Default)]
+pub struct GlobalEventDates {
+    first_seen: HashMap<i64, i64>,
+}
+
+impl GlobalEventDates {
+    /// Record the global events of a Status that were not seen before at its war time
+    pub fn record(&mut self, status: &Status) {
+        for event in &status.global_events {
+            self.first_seen.entry(event.event_id).or_insert(status.time);
+        }
+    }
+
+    /// The war time a global event was first seen at
+    pub fn first_seen(&self, event_id: i64) -> Option<i64> {
+        self.first_seen.get(&event_id).copied()
+    }
+}
+
+/// The details of a generated feed
+#[derive(Debug, Clone)]
+pub struct FeedConfig {
+    pub war_id: i64,
+    pub title: String,
+    /// The web page the feed belongs to
+    pub link: String,
+    /// The URL the feed itself is served at
+    pub self_link: String,
+    pub language: Language,
+    /// Convert highlights to HTML, rather than stripping them to plain text
+    pub html: bool,
+}
+
+impl FeedConfig {
+    pub fn new(war_id: i64, self_link: &str) -> FeedConfig {
+        FeedConfig {
+            war_id,
+            title: "Helldivers 2 Dispatches".to_string(),
+            link: "https://www.arrowheadgamestudios.com/".to_string(),
+            self_link: self_link.to_string(),
+            language: Language::English,
+            html: true,
+        }
+    }
+}
+
+/// An entry of a feed, from a news item or a global event
+struct Entry {
+    guid: String,
+    title: String,
+    content: String,
+    published: i64,
+}
+
+fn entries(news: &[NewsItem], status: Option<&Status>, dates: &GlobalEventDates, clock: &WarClock, config: &FeedConfig) -> Vec<Entry> {
+    let content = |markup: &Markup| if config.html { markup.to_html() } else { markup.to_plain_text() };
+    let mut entries: Vec<Entry> = news.iter().map(|item| {
+        let markup = item.markup();
+        Entry {
+            guid: format!("urn:helldivers:war:{}:news:{}", config.war_id, item.id),
+            title: title(&markup.to_plain_text()),
+            content: content(&markup),
+            published: clock.unix(item.published),
+        }
+    }).collect();
+
+    // global events have no publication time, so they are dated when they were first seen, or else at the Status
+    if let Some(status) = status {
+        for event in &status.global_events {
+            let message = event.message_markup();
+            let event_title = event.title_markup().to_plain_text();
+            entries.push(Entry {
+                guid: format!("urn:helldivers:war:{}:event:{}", config.war_id, event.event_id),
+                title: if event_title.trim().is_empty() { title(&message.to_plain_text()) } else { event_title.trim().to_string() },
+                content: content(&message),
+                published: clock.unix(dates.first_seen(event.event_id).unwrap_or(status.time)),
+            });
+        }
+    }
+    entries.sort_by(|a, b| b.published.cmp(&a.published).then(a.guid.cmp(&b.guid)));
+    entries
+}
+
+/// The first line of a message, shortened to `TITLE_LENGTH` characters
+fn title(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    if line.chars().count() <= TITLE_LENGTH {
+        return line.to_string();
+    }
+    let mut short: String = line.chars().take(TITLE_LENGTH - 1).collect();
+    short.truncate(short.trim_end().len());
+    short.push('…');
+    short
+}
+
+/// Generate an Atom feed of news items and global events, newest first
+///
+/// Arguments:
+///   news: &[NewsItem] - The news items, e.g. from `get_news_feed`
+///   status: Option<&Status> - The Status to take the global events from
+///   dates: &GlobalEventDates - When the global events were first seen, events missing from it are dated at the Status
+///   clock: &WarClock - The clock to convert war time to dates with
+///   config: &FeedConfig - The title, links and content format of the feed
+pub fn atom_feed(news: &[NewsItem], status: Option<&Status>, dates: &GlobalEventDates, clock: &WarClock, config: &FeedConfig) -> String {
+    let entries = entries(news, status, dates, clock, config);
+    let updated = entries.iter().map(|e| e.published).max()
+        .or(status.map(|s| clock.unix(s.time)))
+        .unwrap_or(clock.start);
+    let content_type = if config.html { "html" } else { "text" };
+
+    // writing to a String never fails, so the results of writeln! are ignored
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    let _ = writeln!(xml, "<feed xmlns=\"http://www.w3.org/2005/Atom\" xml:lang=\"{}\">", escape(config.language.to_str()));
+    let _ = writeln!(xml, "  <id>urn:helldivers:war:{}:feed</id>", config.war_id);
+    let _ = writeln!(xml, "  <title>{}</title>", escape(&config.title));
+    let _ = writeln!(xml, "  <updated>{}</updated>", rfc3339(updated));
+    let _ = writeln!(xml, "  <link rel=\"alternate\" href=\"{}\"/>", escape(&config.link));
+    let _ = writeln!(xml, "  <link rel=\"self\" href=\"{}\"/>", escape(&config.self_link));
+    let _ = writeln!(xml, "  <author><name>Super Earth High Command</name></author>");
+    for entry in &entries {
+        let _ = writeln!(xml, "  <entry>");
+        let _ = writeln!(xml, "    <id>{}</id>", escape(&entry.guid));
+        let _ = writeln!(xml, "    <title>{}</title>", escape(&entry.title));
+        let _ = writeln!(xml, "    <published>{}</published>", rfc3339(entry.published));
+        let _ = writeln!(xml, "    <updated>{}</updated>", rfc3339(entry.published));
+        let _ = writeln!(xml, "    <content type=\"{}\">{}</content>", content_type, escape(&entry.content));
+        let _ = writeln!(xml, "  </entry>");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// Generate an RSS 2.0 feed of news items and global events, newest first
+///
+/// Arguments:
+///   news: &[NewsItem] - The news items, e.g. from `get_news_feed`
+///   status: Option<&Status> - The Status to take the global events from
+///   dates: &GlobalEventDates - When the global events were first seen, events missing from it are dated at the Status
+///   clock: &WarClock - The clock to convert war time to dates with
+///   config: &FeedConfig - The title, links and content format of the feed
+pub fn rss_feed(news: &[NewsItem], status: Option<&Status>, dates: &GlobalEventDates, clock: &WarClock, config: &FeedConfig) -> String {
+    let entries = entries(news, status, dates, clock, config);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n  <channel>\n");
+    let _ = writeln!(xml, "    <title>{}</title>", escape(&config.title));
+    let _ = writeln!(xml, "    <link>{}</link>", escape(&config.link));
+    let _ = writeln!(xml, "    <description>{}</description>", escape(&config.title));
+    let _ = writeln!(xml, "    <language>{}</language>", escape(&config.language.to_str().to_lowercase()));
+    let _ = writeln!(xml, "    <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>", escape(&config.self_link));
+    if let Some(latest) = entries.first() {
+        let _ = writeln!(xml, "    <lastBuildDate>{}</lastBuildDate>", rfc2822(latest.published));
+    }
+    for entry in &entries {
+        let _ = writeln!(xml, "    <item>");
+        let _ = writeln!(xml, "      <guid isPermaLink=\"false\">{}</guid>", escape(&entry.guid));
+        let _ = writeln!(xml, "      <title>{}</title>", escape(&entry.title));
+        let _ = writeln!(xml, "      <pubDate>{}</pubDate>", rfc2822(entry.published));
+        let _ = writeln!(xml, "      <description>{}</description>", escape(&entry.content));
+        let _ = writeln!(xml, "    </item>");
+    }
+    xml.push_str("  </channel>\n</rss>\n");
+    xml
+}
+
+/// Escape text for XML, dropping control characters XML 1.0 does not allow
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The UTC date and time of a Unix time, as (year, month, day, weekday from Sunday, hour, minute, second)
+fn civil(unix: i64) -> (i64, u32, u32, u32, u32, u32, u32) {
+    let days = unix.div_euclid(86400);
+    let seconds = unix.rem_euclid(86400) as u32;
+    // days to a proleptic Gregorian date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    // 1970-01-01 was a Thursday
+    let weekday = (days + 4).rem_euclid(7) as u32;
+    (year, month, day, weekday, seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// Format a Unix time as an RFC 3339 date, as used by Atom
+pub(crate) fn rfc3339(unix: i64) -> String {
+    let (year, month, day, _, hour, minute, second) = civil(unix);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, minute, second)
+}
+
+/// Format a Unix time as an RFC 2822 date, as used by RSS
+pub(crate) fn rfc2822(unix: i64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let (year, month, day, weekday, hour, minute, second) = civil(unix);
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} +0000",
+        WEEKDAYS[weekday as usize], day, MONTHS[month as usize - 1], year, hour, minute, second
+    )
+}
@@ -6,31 +6,81 @@ mod models;
 mod requests;
 mod utils;
 mod error;
+mod search;
+mod registry;
+mod reconcile;
+mod markup;
+mod snapshot;
+mod export;
+mod watch;
+mod feed;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "storage")]
+pub mod storage;
+#[cfg(feature = "exporter")]
+pub mod exporter;
+#[cfg(feature = "server")]
+pub mod server;
 
-pub use models::api::{Status, WarInfo, PlanetStatus, PlanetAttack, Campaign, GlobalEvent, HomeWorld, Position, PlanetInfo, WarTime, NewsItem};
-pub use models::{Planet, Faction, Sector, Language};
-pub use requests::{get_status, get_war_info, get_war_time, get_news_feed};
-pub use utils::{get_total_player_count, get_top_planets_by_player_count, get_faction_distribution, get_sectors, get_factions};
+pub use error::HelldiversError;
+pub use models::api::{Status, WarInfo, PlanetStatus, PlanetAttack, Campaign, PlanetEvent, GlobalEvent, HomeWorld, Position, PlanetInfo, WarTime, WarId, NewsItem};
+pub use models::{CampaignType, CampaignView};
+pub use models::{NewsType, NewsTag, NewsCategory, classify_news, NewsFeedQuery, NewsCursor};
+pub use models::{Translations, TranslatedNewsItem, TranslatedGlobalEvent};
+pub use models::{Planet, Biome, Hazard, Faction, Sector, SectorSummary, SectorConsistency, Language, FrontPlanet, HUMAN_FACTION_ID};
+pub use registry::{Registry, REGISTRY, registry, merge_registry, set_registry, load_registry_file};
+pub use reconcile::{reconcile_resources, ResourceUpdate, SectorReassignment, SectorRenumbering};
+pub use export::{planet_rows, PlanetRow};
+#[cfg(feature = "csv")]
+pub use export::write_csv;
+#[cfg(feature = "parquet")]
+pub use export::{planet_rows_to_record_batch, write_parquet};
+pub use feed::{atom_feed, rss_feed, FeedConfig, GlobalEventDates, WarClock};
+pub use watch::{diff_status, StatusWatcher, WarChange};
+pub use snapshot::{fetch_snapshot, WarSnapshot, PlanetView, EndpointError};
+pub use markup::{parse_markup, Markup, MarkupNode};
+pub use search::{find_planets, find_sectors, find_planet, find_sector, normalize_name, NameMatch, MIN_MATCH_SCORE};
+pub use requests::{current_war_id, refresh_current_war_id, WAR_ID_CACHE_TTL, NEWS_PAGE_SIZE};
+pub use requests::{get_status, get_war_info, get_war_time, get_news_feed, get_news_feed_with, news_since, get_translations};
+pub use utils::{get_total_player_count, get_top_planets_by_player_count, get_faction_distribution, get_sectors, get_factions, get_front_lines, get_sector_summaries, get_top_sectors_by_activity, get_campaigns};
 
 /// The base URL for the Helldivers API
 pub const BASE_URL: &str = "https://api.live.prod.thehelldiversgame.com/api";
 
-/// The planets in the game
+/// The planets bundled with the crate, see `REGISTRY` for the planets used to resolve names
 pub static PLANETS: Lazy<HashMap<i64, Planet>> = Lazy::new(load_res::load_planets);
 
-/// The active factions in the game
+/// The planet biomes bundled with the crate, by slug
+pub static BIOMES: Lazy<HashMap<String, Biome>> = Lazy::new(load_res::load_biomes);
+
+/// The environmental hazards bundled with the crate, by slug
+pub static HAZARDS: Lazy<HashMap<String, Hazard>> = Lazy::new(load_res::load_hazards);
+
+/// The factions bundled with the crate
 pub static FACTIONS: Lazy<HashMap<i64, models::Faction>> = Lazy::new(load_res::load_factions);
 
-/// The sectors in the game
+/// The sectors bundled with the crate
 pub static SECTORS: Lazy<HashMap<i64, models::Sector>> = Lazy::new(load_res::load_sectors);
 
+/// The sector ID of each planet bundled with the crate
+pub static PLANET_SECTORS: Lazy<HashMap<i64, i64>> = Lazy::new(load_res::load_planet_sectors);
+
+
+/// Get a planet, including its biome and environmental hazards
+///
+/// Arguments:
+///   id: i64 - The ID of the planet
+pub fn get_planet(id: i64) -> Option<Planet> {
+    registry().planet(id)
+}
 
 /// Get the name of a planet
 ///
 /// Arguments:
 ///   id: i64 - The ID of the planet
 pub fn get_planet_name(id: i64) -> Option<String> {
-    PLANETS.get(&id).map(|planet| planet.name.clone())
+    registry().planet_name(id)
 }
 
 /// Get the name of a faction
@@ -38,7 +88,7 @@ pub fn get_planet_name(id: i64) -> Option<String> {
 /// Arguments:
 ///    id: i64 - The ID of the faction
 pub fn get_faction_name(id: i64) -> Option<String> {
-    FACTIONS.get(&id).map(|faction| faction.name.clone())
+    registry().faction_name(id)
 }
 
 /// Get the name of a sector
@@ -46,40 +96,164 @@ pub fn get_faction_name(id: i64) -> Option<String> {
 /// Arguments:
 ///   id: i64 - The ID of the sector
 pub fn get_sector_name(id: i64) -> Option<String> {
-    SECTORS.get(&id).map(|sector| sector.name.clone())
+    registry().sector_name(id)
+}
+
+/// Get the name of a planet in a language, falling back to the English name
+///
+/// Arguments:
+///   id: i64 - The ID of the planet
+///   language: Language - The language of the name
+pub fn get_localized_planet_name(id: i64, language: Language) -> Option<String> {
+    registry().localized_planet_name(id, language)
+}
+
+/// Get the name of a faction in a language, falling back to the English name
+///
+/// Arguments:
+///   id: i64 - The ID of the faction
+///   language: Language - The language of the name
+pub fn get_localized_faction_name(id: i64, language: Language) -> Option<String> {
+    registry().localized_faction_name(id, language)
+}
+
+/// Get the name of a sector in a language, falling back to the English name
+///
+/// Arguments:
+///   id: i64 - The ID of the sector
+///   language: Language - The language of the name
+pub fn get_localized_sector_name(id: i64, language: Language) -> Option<String> {
+    registry().localized_sector_name(id, language)
+}
+
+/// Get the ID of the sector a planet belongs to
+///
+/// Arguments:
+///   id: i64 - The ID of the planet
+pub fn get_planet_sector(id: i64) -> Option<i64> {
+    registry().planet_sector(id)
+}
+
+/// Check the bundled sector table for planets in several sectors or none, and for sector IDs used more than once
+pub fn check_sector_consistency() -> SectorConsistency {
+    load_res::check_sectors(&PLANETS)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// A small offline war: Super Earth (0) links to 1 and 2, 1 links to the terminid planet 3,
+    /// 2 links to the automaton planet 4
+    fn sample_war() -> (WarInfo, Status) {
+        let planet_info = |index: i64, x: f64, y: f64, waypoints: Vec<i64>, sector: i64| serde_json::json!({
+            "index": index, "settingsHash": 0, "position": {"x": x, "y": y}, "waypoints": waypoints,
+            "sector": sector, "maxHealth": 1000000, "disabled": false, "initialOwner": 1
+        });
+        let planet_status = |index: i64, owner: i64, health: i64, players: i64| serde_json::json!({
+            "index": index, "owner": owner, "health": health, "regenPerSecond": 1.5, "players": players
+        });
+        let war_info = serde_json::from_value(serde_json::json!({
+            "warId": 801, "startDate": 1706040313, "endDate": 1833653095, "minimumClientVersion": "0.3.0",
+            "planetInfos": [
+                planet_info(0, 0.0, 0.0, vec![1, 2], 0),
+                planet_info(1, 0.1, 0.1, vec![3], 1),
+                planet_info(2, -0.1, 0.1, vec![], 1),
+                planet_info(3, 0.2, 0.3, vec![], 2),
+                planet_info(4, -0.2, 0.3, vec![2], 3),
+            ],
+            "homeWorlds": [{"race": 1, "planetIndices": [0]}]
+        })).unwrap();
+        let status = serde_json::from_value(serde_json::json!({
+            "warId": 801, "time": 1000, "impactMultiplier": 0.05, "storyBeatId32": 0,
+            "planetStatus": [
+                planet_status(0, 1, 1000000, 10),
+                planet_status(1, 1, 1000000, 500),
+                planet_status(2, 1, 600000, 300),
+                planet_status(3, 2, 400000, 2000),
+                planet_status(4, 3, 1000000, 0),
+            ],
+            "planetAttacks": [{"source": 3, "target": 1}],
+            "campaigns": [{"id": 1, "planetIndex": 3, "type": 0, "count": 1}],
+            "globalEvents": []
+        })).unwrap();
+        (war_info, status)
+    }
+
+    /// Add a terminid attack on the human planet 2 to `sample_war`, as a campaign with a planet event
+    fn add_defense_campaign(status: &mut Status) {
+        status.campaigns.push(serde_json::from_value(serde_json::json!({"id": 2, "planetIndex": 2, "type": 0, "count": 1})).unwrap());
+        status.planet_events.push(serde_json::from_value(serde_json::json!({
+            "id": 5, "planetIndex": 2, "eventType": 1, "race": 2, "health": 250000, "maxHealth": 1000000,
+            "startTime": 900, "expireTime": 90000, "campaignId": 2, "jointOperationIds": [4]
+        })).unwrap());
+    }
+
+    /// A global event highlighting the front between planets 1 and 3 of `sample_war`
+    fn sample_global_event() -> GlobalEvent {
+        serde_json::from_value(serde_json::json!({
+            "eventId": 1, "id32": 0, "portraitId32": 0, "title": "BRIEFING", "titleId32": 0,
+            "message": "Hold the line on <i=1>Pathfinder V</i>.", "messageId32": 0, "race": 2, "flag": 0,
+            "assignmentId32": 0, "planetIndices": [1, 3]
+        })).unwrap()
+    }
+
+    /// The current war, for tests against the live API
+    async fn live_war_id() -> i64 {
+        match current_war_id().await {
+            Ok(war_id) => war_id,
+            Err(e) => panic!("Error: {}", e),
+        }
+    }
+
     #[tokio::test]
     async fn test_get_status() {
-        let _status = match get_status(801, Language::English).await{
+        let _status = match get_status(live_war_id().await, Language::English).await{
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
     }
 
+    #[tokio::test]
+    async fn test_fetch_snapshot() {
+        let snapshot = match fetch_snapshot(live_war_id().await, Language::English).await {
+            Ok(snapshot) => snapshot,
+            Err(e) => panic!("Error: {}", e),
+        };
+        assert_eq!(snapshot.planets().len(), snapshot.war_info.planet_infos.len());
+    }
+
+    #[tokio::test]
+    async fn test_current_war_id() {
+        let war_id = match current_war_id().await {
+            Ok(war_id) => war_id,
+            Err(e) => panic!("Error: {}", e),
+        };
+        assert!(get_war_info(war_id).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_get_war_info() {
-        let _war_info = match get_war_info(801).await {
+        let _war_info = match get_war_info(live_war_id().await).await {
             Ok(war_info) => war_info,
             Err(e) => panic!("Error: {}", e),
         };
     }
 
     #[test]
+    #[allow(clippy::len_zero)]
     fn test_load_planets() {
         assert!(PLANETS.len() > 0);
     }
 
     #[test]
+    #[allow(clippy::len_zero)]
     fn test_load_factions() {
         assert!(FACTIONS.len() > 0);
     }
 
     #[test]
+    #[allow(clippy::len_zero)]
     fn test_load_sectors() {
         assert!(SECTORS.len() > 0);
     }
@@ -101,7 +275,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_war_info_planet_name() {
-        let war_info = match get_war_info(801).await {
+        let war_info = match get_war_info(live_war_id().await).await {
             Ok(war_info) => war_info,
             Err(e) => panic!("Error: {}", e),
         };
@@ -112,7 +286,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_status_planet_name() {
-        let status = match get_status(801, Language::English).await {
+        let status = match get_status(live_war_id().await, Language::English).await {
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
@@ -123,12 +297,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_alternate_language() {
-        let de_status = match get_status(801, Language::German).await {
+        let de_status = match get_status(live_war_id().await, Language::German).await {
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
 
-        let en_status = match get_status(801, Language::English).await {
+        let en_status = match get_status(live_war_id().await, Language::English).await {
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
@@ -143,7 +317,7 @@ mod tests {
     
     #[tokio::test]
     async fn test_total_player_count() {
-        let status = match get_status(801, Language::English).await {
+        let status = match get_status(live_war_id().await, Language::English).await {
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
@@ -152,7 +326,7 @@ mod tests {
     
     #[tokio::test]
     async fn test_top_planets_by_player_count() {
-        let status = match get_status(801, Language::English).await {
+        let status = match get_status(live_war_id().await, Language::English).await {
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
@@ -162,7 +336,7 @@ mod tests {
     
     #[tokio::test]
     async fn test_faction_distribution() {
-        let status = match get_status(801, Language::English).await {
+        let status = match get_status(live_war_id().await, Language::English).await {
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
@@ -172,7 +346,7 @@ mod tests {
     
     #[tokio::test]
     async fn test_war_time() {
-        let _ = match get_war_time(801).await {
+        let _ = match get_war_time(live_war_id().await).await {
             Ok(war_time) => war_time,
             Err(e) => panic!("Error: {}", e),
         };
@@ -180,7 +354,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_news_feed() {
-        let news_feed = match get_news_feed(801, Language::English).await {
+        let news_feed = match get_news_feed(live_war_id().await, Language::English).await {
             Ok(news_feed) => news_feed,
             Err(e) => panic!("Error: {}", e),
         };
@@ -190,7 +364,7 @@ mod tests {
     #[tokio::test]
     async fn test_sector_planet_mapping () {
         // Ensure that our sector to planet mapping is correct
-        let war_info = match get_war_info(801).await {
+        let war_info = match get_war_info(live_war_id().await).await {
             Ok(war_info) => war_info,
             Err(e) => panic!("Error: {}", e),
         };
@@ -210,7 +384,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_sectors() {
-        let war_info = match get_war_info(801).await {
+        let war_info = match get_war_info(live_war_id().await).await {
             Ok(war_info) => war_info,
             Err(e) => panic!("Error: {}", e),
         };
@@ -220,11 +394,845 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_factions() {
-        let status = match get_status(801, Language::English).await {
+        let status = match get_status(live_war_id().await, Language::English).await {
             Ok(status) => status,
             Err(e) => panic!("Error: {}", e),
         };
         let factions = get_factions(&status);
         assert!(!factions.is_empty());
     }
+
+    #[test]
+    fn test_get_front_lines() {
+        let (war_info, status) = sample_war();
+        let fronts = get_front_lines(&war_info, &status);
+        assert_eq!(fronts.len(), 2);
+
+        let terminid_front = &fronts[&2];
+        assert_eq!(terminid_front.len(), 1);
+        assert_eq!(terminid_front[0].status.index, 1);
+        assert_eq!(terminid_front[0].enemy_neighbours, vec![3]);
+        assert_eq!(terminid_front[0].attacks.len(), 1);
+        assert_eq!(terminid_front[0].campaigns.len(), 1);
+
+        // planet 4 lists the waypoint, planet 2 does not
+        let automaton_front = &fronts[&3];
+        assert_eq!(automaton_front[0].status.index, 2);
+        assert!(automaton_front[0].attacks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_live_front_lines() {
+        let war_info = match get_war_info(live_war_id().await).await {
+            Ok(war_info) => war_info,
+            Err(e) => panic!("Error: {}", e),
+        };
+        let status = match get_status(live_war_id().await, Language::English).await {
+            Ok(status) => status,
+            Err(e) => panic!("Error: {}", e),
+        };
+        let fronts = get_front_lines(&war_info, &status);
+        for planet in fronts.values().flatten() {
+            assert_eq!(planet.status.owner, HUMAN_FACTION_ID);
+            assert!(!planet.enemy_neighbours.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_get_sector_summaries() {
+        let (war_info, status) = sample_war();
+        let summaries = get_sector_summaries(&war_info, &status);
+        assert_eq!(summaries.len(), 4);
+
+        let sector = summaries.iter().find(|s| s.id == 1).unwrap();
+        assert_eq!(sector.planets, vec![1, 2]);
+        assert_eq!(sector.owners[&HUMAN_FACTION_ID], 2);
+        assert_eq!(sector.contested_planets, vec![2]);
+        assert_eq!(sector.players, 800);
+        assert!(sector.liberated);
+
+        let sector = summaries.iter().find(|s| s.id == 2).unwrap();
+        assert_eq!(sector.contested_planets, vec![3]);
+        assert!(!sector.liberated);
+    }
+
+    #[test]
+    fn test_get_top_sectors_by_activity() {
+        let (war_info, status) = sample_war();
+        let top = get_top_sectors_by_activity(&war_info, &status, 2);
+        assert_eq!(top.iter().map(|s| s.id).collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[cfg(feature = "render")]
+    #[test]
+    fn test_render_svg() {
+        let (war_info, status) = sample_war();
+        let svg = render::render_svg(&war_info, &status, &render::MapStyle::default());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<circle").count(), 5 + status.campaigns.len());
+        assert!(svg.contains("marker-end"));
+        assert!(svg.contains(">SOL<"));
+    }
+
+    #[cfg(feature = "raster")]
+    #[test]
+    fn test_render_png() {
+        let (war_info, status) = sample_war();
+        let style = render::MapStyle { width: 256, height: 128, ..Default::default() };
+        let highlight = render::Highlight::from(&sample_global_event());
+        let png = render::render_png(&war_info, &status, &style, Some(&highlight)).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        // the IHDR chunk holds the image size
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 256);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 128);
+    }
+
+    #[test]
+    fn test_find_planet() {
+        assert_eq!(find_planet("widows harbour"), Some(3));
+        assert_eq!(find_planet("WIDOW'S HARBOR"), Some(3));
+        assert_eq!(find_planet("hellmire"), Some(34));
+        assert_eq!(find_planet("malevelon"), Some(196));
+        assert_eq!(find_planet("196"), Some(196));
+        assert_eq!(find_planet("xyzzy"), None);
+    }
+
+    #[test]
+    fn test_find_planets_ranking() {
+        let matches = find_planets("helmire", 3);
+        assert_eq!(matches[0].name, "Hellmire");
+        assert!(matches.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_find_sector() {
+        assert_eq!(find_sector("lestrade").and_then(get_sector_name), Some("L'estrade".to_string()));
+        assert_eq!(find_sector("SOL"), Some(0));
+        assert_eq!(find_sector("0"), Some(0));
+        for name in ["Farsight", "Ursa", "Mirin", "Hydra", "Akira", "Nanos"] {
+            assert_eq!(find_sector(name).and_then(get_sector_name).as_deref(), Some(name));
+        }
+
+        // names sharing an ID are all searched, rather than only the one the ID map kept
+        let mut registry = Registry::from_toml_str(r#"
+            [sectors.Outer]
+            id = 900
+            planets = [9000]
+            [sectors.Fringe]
+            id = 900
+            planets = [9001]
+        "#).unwrap();
+        registry.merge(Registry::from_toml_str("[sectors.Rim]\nid = 901\nplanets = [9002]").unwrap());
+        for name in ["outer", "fringe", "rim"] {
+            let best = search::rank_sectors(&registry, name, 1);
+            assert_eq!(best.first().map(|m| (m.name.to_lowercase(), m.score)), Some((name.to_string(), 1.0)));
+        }
+        assert_eq!(search::rank_sectors(&registry, "fringe", 1)[0].id, 900);
+    }
+
+    #[test]
+    fn test_get_planet_sector() {
+        assert_eq!(get_planet_sector(0), Some(0));
+        // every planet in a sector is indexed
+        for sector in SECTORS.values() {
+            for planet in &sector.planets {
+                assert_eq!(get_planet_sector(*planet), Some(sector.id));
+            }
+        }
+        assert_eq!(PLANET_SECTORS.len(), PLANETS.len());
+    }
+
+    #[test]
+    fn test_sector_consistency() {
+        let consistency = check_sector_consistency();
+        assert!(consistency.is_consistent(), "{:?}", consistency);
+        assert_eq!(get_planet_sector(34).and_then(get_sector_name).as_deref(), Some("Mirin"));
+    }
+
+    #[test]
+    fn test_registry_from_toml() {
+        let registry = Registry::from_toml_str(r#"
+            [planets]
+            9000 = "New Planet"
+            [factions]
+            9 = "Newcomers"
+            [sectors."New Sector"]
+            id = 900
+            planets = [9000]
+        "#).unwrap();
+        assert_eq!(registry.planet_name(9000).unwrap(), "New Planet");
+        assert_eq!(registry.faction_name(9).unwrap(), "Newcomers");
+        assert_eq!(registry.sector_name(900).unwrap(), "New Sector");
+        assert_eq!(registry.planet_sector(9000), Some(900));
+        assert_eq!(registry.planet(9000).unwrap().sector, Some(900));
+        assert!(Registry::from_toml_str("[planets]\nabc = \"Nope\"").is_err());
+    }
+
+    #[test]
+    fn test_registry_merge() {
+        let mut registry = Registry::bundled();
+        registry.merge(Registry::from_json_str(r#"{
+            "planets": {"0": "Super Earth Prime"},
+            "sectors": {"Sol Annex": {"id": 901, "planets": [0]}}
+        }"#).unwrap());
+        assert_eq!(registry.planet_name(0).unwrap(), "Super Earth Prime");
+        assert_eq!(registry.planet_name(1), get_planet_name(1));
+        assert_eq!(registry.planet_sector(0), Some(901));
+        assert_eq!(registry.planet(0).unwrap().sector, Some(901));
+        assert!(!registry.sectors[&0].planets.contains(&0));
+    }
+
+    #[test]
+    fn test_registry_merge_replaces_sector() {
+        let mut registry = Registry::bundled();
+        let sol = registry.sectors[&0].planets.clone();
+        registry.merge(Registry::from_json_str(r#"{
+            "planets": {"9001": "Runtime Planet"},
+            "sectors": {"Sol": {"id": 0, "planets": [9001]}}
+        }"#).unwrap());
+        assert_eq!(registry.planet_name(9001).unwrap(), "Runtime Planet");
+        assert_eq!(registry.planet_sector(9001), Some(0));
+        // the planets the replaced sector dropped no longer point at it
+        for planet in sol {
+            assert_eq!(registry.planet_sector(planet), None);
+        }
+    }
+
+    #[test]
+    fn test_reconcile_resources() {
+        let (mut war_info, _) = sample_war();
+        // put every planet in the sector the bundled table has for it, then move one and add an unknown planet
+        for planet_info in &mut war_info.planet_infos {
+            planet_info.sector = get_planet_sector(planet_info.index).unwrap();
+        }
+        let moved_from = war_info.planet_infos[1].sector;
+        war_info.planet_infos[1].sector = 999;
+        let mut new_planet = serde_json::to_value(&war_info.planet_infos[0]).unwrap();
+        new_planet["index"] = serde_json::json!(5000);
+        war_info.planet_infos.push(serde_json::from_value(new_planet).unwrap());
+
+        let update = reconcile_resources(&war_info);
+        assert_eq!(update.unnamed_planets, vec![5000]);
+        assert_eq!(update.new_sectors, vec![999]);
+        assert!(update.sector_reassignments.contains(&SectorReassignment { planet: 1, from: Some(moved_from), to: 999 }));
+        assert!(!update.is_empty());
+
+        // the output is valid TOML in the format of the bundled files
+        let planets: HashMap<String, toml::Value> = toml::from_str(&update.planets_toml).unwrap();
+        assert_eq!(planets["0"]["name"].as_str(), Some("Super Earth"));
+        assert_eq!(planets["0"]["names"]["de-DE"].as_str(), Some("Super-Erde"));
+        assert_eq!(planets["1"].as_str(), Some("Klen Dahth II"));
+        assert_eq!(planets["5000"].as_str(), Some(""));
+        assert_eq!(planets["34"]["hazards"].as_array().unwrap().len(), 2);
+        let sectors: HashMap<String, toml::Value> = toml::from_str(&update.sectors_toml).unwrap();
+        assert_eq!(sectors["Sector 999"]["planets"].as_array().unwrap().len(), 1);
+        assert_eq!(sectors["Sol"]["planets"].as_array().unwrap().len(), 2);
+
+        // planets with an empty name count as unnamed
+        let mut planets = PLANETS.clone();
+        planets.get_mut(&1).unwrap().name = " ".to_string();
+        let update = reconcile::reconcile_tables(&war_info, &planets, load_res::parse_sectors());
+        assert_eq!(update.unnamed_planets, vec![1, 5000]);
+        assert!(update.planets_toml.contains("\n1 = \" \" # ToDo: name this planet\n"));
+    }
+
+    #[test]
+    fn test_get_planet_details() {
+        let hellmire = get_planet(34).unwrap();
+        assert_eq!(hellmire.name, "Hellmire");
+        assert_eq!(hellmire.biome.unwrap().slug, "scorched");
+        assert_eq!(hellmire.hazards.iter().map(|h| h.name.as_str()).collect::<Vec<_>>(), vec!["Intense Heat", "Fire Tornadoes"]);
+
+        assert_eq!(hellmire.sector, get_planet_sector(34));
+        assert!(hellmire.sector.is_some());
+
+        let super_earth = get_planet(0).unwrap();
+        assert!(super_earth.biome.is_none());
+        assert!(super_earth.hazards.is_empty());
+        assert!(super_earth.description.is_some());
+        assert_eq!(super_earth.sector, Some(0));
+
+        let (war_info, status) = sample_war();
+        assert_eq!(status.planet_status[0].planet().unwrap().name, "Super Earth");
+        assert_eq!(war_info.planet_infos[1].planet().unwrap().id, 1);
+    }
+
+    #[test]
+    fn test_registry_planet_details() {
+        let registry = Registry::from_toml_str(r#"
+            [planets]
+            9002 = { name = "Acid World", biome = "toxic", hazards = ["acid_storms"], description = "Not a holiday destination." }
+        "#).unwrap();
+        let planet = registry.planet(9002).unwrap();
+        assert_eq!(planet.hazards[0].slug, "acid_storms");
+        assert_eq!(planet.description.as_deref(), Some("Not a holiday destination."));
+        assert!(Registry::from_toml_str(r#"planets = { 9003 = { name = "Nowhere", biome = "plaid" } }"#).is_err());
+    }
+
+    #[test]
+    fn test_get_localized_names() {
+        assert_eq!(get_localized_planet_name(0, Language::German).unwrap(), "Super-Erde");
+        assert_eq!(get_localized_planet_name(0, Language::English).unwrap(), "Super Earth");
+        // planets without a translation keep their English name
+        assert_eq!(get_localized_planet_name(34, Language::Polish).unwrap(), "Hellmire");
+        assert_eq!(get_localized_faction_name(2, Language::German).unwrap(), "Terminiden");
+        assert_eq!(get_localized_faction_name(1, Language::Polish).unwrap(), "Ludzie");
+        assert_eq!(get_localized_sector_name(0, Language::Chinese), get_sector_name(0));
+        assert_eq!(get_localized_planet_name(-1, Language::German), None);
+        // every bundled faction has a name in every language
+        for faction in FACTIONS.values() {
+            for language in Language::all().filter(|language| *language != Language::English) {
+                assert!(faction.names.contains_key(language.to_str()), "{} has no {} name", faction.name, language.to_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_registry_localized_names() {
+        let registry = Registry::from_toml_str(r#"
+            [planets]
+            9004 = { name = "Frostfall", names = { de-DE = "Frostfall-Ebene" } }
+            [factions]
+            9 = { name = "Newcomers", names = { pl-PL = "Przybysze" } }
+            [sectors.Borealis]
+            id = 902
+            planets = [9004]
+            names = { fr-FR = "Boréalis" }
+        "#).unwrap();
+        assert_eq!(registry.localized_planet_name(9004, Language::German).unwrap(), "Frostfall-Ebene");
+        assert_eq!(registry.localized_faction_name(9, Language::Polish).unwrap(), "Przybysze");
+        assert_eq!(registry.localized_sector_name(902, Language::French).unwrap(), "Boréalis");
+        assert_eq!(registry.localized_sector_name(902, Language::Italian).unwrap(), "Borealis");
+    }
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!("de".parse::<Language>().unwrap(), Language::German);
+        assert_eq!("de-DE".parse::<Language>().unwrap(), Language::German);
+        assert_eq!("German".parse::<Language>().unwrap(), Language::German);
+        assert_eq!("PL_pl".parse::<Language>().unwrap(), Language::Polish);
+        assert_eq!("zh-Hans".parse::<Language>().unwrap(), Language::Chinese);
+        assert!("Klingon".parse::<Language>().is_err());
+        for language in Language::all() {
+            assert_eq!(language.to_string().parse::<Language>().unwrap(), language);
+        }
+        assert_eq!(Language::all().count(), 8);
+    }
+
+    #[test]
+    fn test_language_serde() {
+        assert_eq!(serde_json::to_string(&Language::French).unwrap(), r#""fr-FR""#);
+        assert_eq!(serde_json::from_str::<Language>(r#""Italian""#).unwrap(), Language::Italian);
+        assert!(serde_json::from_str::<Language>(r#""tlh""#).is_err());
+    }
+
+    #[test]
+    fn test_language_negotiation() {
+        assert_eq!(Language::from_locale("de-AT"), Some(Language::German));
+        assert_eq!(Language::from_locale("zh-TW"), Some(Language::Chinese));
+        assert_eq!(Language::from_locale("pt-BR"), Some(Language::Spanish));
+        assert_eq!(Language::from_locale("uk"), Some(Language::Russian));
+        assert_eq!(Language::from_locale("ja"), None);
+
+        assert_eq!(Language::from_accept_language("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5"), Language::French);
+        assert_eq!(Language::from_accept_language("ja;q=0.9, pl;q=0.4, de;q=0.6"), Language::German);
+        assert_eq!(Language::from_accept_language("it;q=0, es;q=0.2"), Language::Spanish);
+        assert_eq!(Language::from_accept_language("ja, ko"), Language::English);
+        // a supported language with a lower q-value wins over a related language
+        assert_eq!(Language::from_accept_language("pt-BR, en;q=0.8"), Language::English);
+        assert_eq!(Language::from_accept_language("pt-BR, ja;q=0.8"), Language::Spanish);
+        assert_eq!(Language::from_accept_language(""), Language::English);
+    }
+
+    #[test]
+    fn test_collect_translations() {
+        let (_, mut en_status) = sample_war();
+        en_status.global_events.push(sample_global_event());
+        let (_, mut de_status) = sample_war();
+        de_status.global_events.push(sample_global_event());
+        de_status.global_events[0].message = "Haltet die Stellung.".to_string();
+        let news = |message: &str| -> Vec<NewsItem> {
+            serde_json::from_value(serde_json::json!([{"id": 7, "published": 100, "type": 0, "message": message}])).unwrap()
+        };
+
+        let translations = requests::collect_translations(vec![
+            (Language::English, en_status, news("Hold the line.")),
+            (Language::German, de_status, news("Haltet die Stellung.")),
+        ]);
+        assert_eq!(translations.news[&7].published, 100);
+        assert_eq!(translations.news[&7].messages[&Language::German], "Haltet die Stellung.");
+        assert_eq!(translations.news[&7].messages.len(), 2);
+        assert_eq!(translations.global_events[&1].titles[&Language::English], "BRIEFING");
+        assert_eq!(translations.global_events[&1].messages[&Language::German], "Haltet die Stellung.");
+    }
+
+    #[tokio::test]
+    async fn test_get_translations() {
+        let translations = match get_translations(live_war_id().await, [Language::English, Language::German]).await {
+            Ok(translations) => translations,
+            Err(e) => panic!("Error: {}", e),
+        };
+        // a language that failed is missing from every item
+        for item in translations.news.values() {
+            assert_eq!(item.messages.len(), 2 - translations.errors.len());
+        }
+    }
+
+    #[test]
+    fn test_parse_markup() {
+        let markup = parse_markup("Liberate <i=1>Malevelon Creek</i>.\nFor <i=3>Super Earth</i>!");
+        assert_eq!(markup.nodes, vec![
+            MarkupNode::Text("Liberate ".to_string()),
+            MarkupNode::Highlight { style: 1, children: vec![MarkupNode::Text("Malevelon Creek".to_string())] },
+            MarkupNode::Text(".".to_string()),
+            MarkupNode::LineBreak,
+            MarkupNode::Text("For ".to_string()),
+            MarkupNode::Highlight { style: 3, children: vec![MarkupNode::Text("Super Earth".to_string())] },
+            MarkupNode::Text("!".to_string()),
+        ]);
+
+        // unclosed highlights run to the end, stray closing tags are dropped and unknown tags are text
+        let markup = parse_markup("a</i> <b>b <i=1>c");
+        assert_eq!(markup.to_plain_text(), "a <b>b c");
+        assert!(matches!(markup.nodes.last(), Some(MarkupNode::Highlight { .. })));
+    }
+
+    #[test]
+    fn test_render_markup() {
+        let markup = parse_markup("Hold <i=1>Widow's Harbor</i> & <i=1>Fort *Union*</i>\r\nNow");
+        assert_eq!(markup.to_plain_text(), "Hold Widow's Harbor & Fort *Union*\nNow");
+        assert_eq!(markup.to_markdown(), "Hold **Widow's Harbor** & **Fort \\*Union\\***\nNow");
+        assert_eq!(markup.to_html(), "Hold <span class=\"highlight highlight-1\">Widow&#39;s Harbor</span> &amp; <span class=\"highlight highlight-1\">Fort *Union*</span><br>\nNow");
+        assert_eq!(markup.to_ansi(), "Hold \x1b[1;33mWidow's Harbor\x1b[0m & \x1b[1;33mFort *Union*\x1b[0m\nNow");
+
+        let nested = parse_markup("<i=1>a <i=2>b</i> c</i>");
+        assert_eq!(nested.to_markdown(), "**a b c**");
+        assert_eq!(parse_markup("Hold<i=1> the line </i>now").to_markdown(), "Hold **the line** now");
+        assert_eq!(nested.to_ansi(), "\x1b[1;33ma \x1b[1;33mb\x1b[0m\x1b[1;33m c\x1b[0m");
+    }
+
+    #[test]
+    fn test_classify_news() {
+        let news = |news_type: i64, tags: &[&str], message: &str| -> NewsItem {
+            serde_json::from_value(serde_json::json!({"id": 1, "published": 0, "type": news_type, "tagIds": tags, "message": message})).unwrap()
+        };
+
+        let item = news(0, &[], "<i=3>MAJOR ORDER</i>\nLiberate the Severin sector.");
+        assert_eq!(item.news_type(), NewsType::Dispatch);
+        assert_eq!(item.category(Language::English), NewsCategory::MajorOrder);
+        assert_eq!(news(0, &[], "Malevelon Creek has been liberated!").category(Language::English), NewsCategory::PlanetLiberated);
+        assert_eq!(news(0, &[], "Estanu has fallen to the Automatons.").category(Language::English), NewsCategory::PlanetLost);
+        assert_eq!(news(0, &[], "Hellmire is under attack.").category(Language::English), NewsCategory::Defense);
+        for message in ["Hellmire needs Helldivers. Move to defend!", "Estanu must be defended.", "Hold and defend"] {
+            assert_eq!(news(0, &[], message).category(Language::English), NewsCategory::Defense, "{}", message);
+        }
+        // keywords match whole words only
+        assert_eq!(news(0, &[], "New defenders' armor is available.").category(Language::English), NewsCategory::Dispatch);
+        assert_eq!(news(0, &[], "New Warbonds are available.").category(Language::English), NewsCategory::Dispatch);
+        // the keywords are English, so other languages are not classified
+        assert_eq!(news(0, &[], "Hellmire wird angegriffen.").category(Language::German), NewsCategory::Unknown);
+
+        let item = news(5, &["4321"], "Hellmire is under attack.");
+        assert_eq!(item.news_type(), NewsType::Unknown(5));
+        assert_eq!(item.tags(), vec![NewsTag::Unknown("4321".to_string())]);
+        assert_eq!(serde_json::to_value(item.tags()).unwrap(), serde_json::json!(["4321"]));
+        // JSON written with the old field name still reads
+        let item: NewsItem = serde_json::from_value(serde_json::json!({"id": 1, "published": 0, "type": 0, "tag_ids": ["7"], "message": ""})).unwrap();
+        assert_eq!(item.tag_ids, vec!["7".to_string()]);
+    }
+
+    #[test]
+    fn test_news_cursor() {
+        let news = |items: &[(i64, i64)]| -> Vec<NewsItem> {
+            let items: Vec<serde_json::Value> = items.iter()
+                .map(|(id, published)| serde_json::json!({"id": id, "published": published, "type": 0, "message": ""}))
+                .collect();
+            serde_json::from_value(serde_json::Value::Array(items)).unwrap()
+        };
+        let ids = |items: Vec<NewsItem>| -> Vec<i64> { items.iter().map(|item| item.id).collect() };
+
+        let mut cursor = NewsCursor::default();
+        assert_eq!(ids(cursor.advance(news(&[(3, 300), (1, 100), (2, 200), (2, 200)]))), vec![1, 2, 3]);
+        assert_eq!(cursor, NewsCursor { published: 300, id: 3 });
+
+        // the timestamp query is inclusive, so the newest item comes back again
+        assert_eq!(ids(cursor.advance(news(&[(3, 300), (4, 300), (5, 400)]))), vec![4, 5]);
+        assert!(cursor.advance(news(&[(5, 400)])).is_empty());
+        assert_eq!(cursor, NewsCursor { published: 400, id: 5 });
+
+        let query = NewsFeedQuery { from_timestamp: Some(400), max_entries: Some(10) };
+        assert_eq!(query.to_params(), vec![("fromTimestamp", "400".to_string()), ("maxEntries", "10".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_news() {
+        // a feed of 7 items, two of them published at the same time, served in pages of 3
+        let feed: Vec<(i64, i64)> = vec![(1, 100), (2, 200), (3, 300), (4, 300), (5, 400), (6, 500), (7, 600)];
+        let mut queries = Vec::new();
+        let mut cursor = NewsCursor::default();
+        let news = requests::paginate_news(&mut cursor, 3, |query: NewsFeedQuery| {
+            queries.push(query.from_timestamp.unwrap());
+            let page: Vec<serde_json::Value> = feed.iter()
+                .filter(|(_, published)| *published >= query.from_timestamp.unwrap())
+                .take(query.max_entries.unwrap() as usize)
+                .map(|(id, published)| serde_json::json!({"id": id, "published": published, "type": 0, "message": ""}))
+                .collect();
+            async move { Ok(serde_json::from_value(serde_json::Value::Array(page)).unwrap()) }
+        }).await.unwrap();
+        assert_eq!(news.iter().map(|item| item.id).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(cursor, NewsCursor { published: 600, id: 7 });
+        assert_eq!(queries, vec![0, 300, 400, 600]);
+    }
+
+    #[test]
+    fn test_get_campaigns() {
+        let (war_info, mut status) = sample_war();
+        add_defense_campaign(&mut status);
+        let campaigns = get_campaigns(&war_info, &status);
+        assert_eq!(campaigns.len(), 2);
+
+        let liberation = &campaigns[0];
+        assert_eq!(liberation.campaign.id, 1);
+        assert_eq!(liberation.campaign_type, CampaignType::Liberation);
+        assert_eq!(liberation.owner, Some(2));
+        assert_eq!(liberation.race, Some(2));
+        assert_eq!(liberation.race_name, get_faction_name(2).unwrap());
+        assert_eq!(liberation.sector, Some(2));
+        assert!(liberation.event.is_none());
+        assert!((liberation.progress().unwrap() - 0.6).abs() < 1e-9);
+
+        let defense = &campaigns[1];
+        assert_eq!(defense.campaign.campaign_type(), CampaignType::Liberation);
+        assert_eq!(defense.campaign_type, CampaignType::Defense);
+        assert_eq!(defense.race, Some(2));
+        assert_eq!(defense.owner, Some(HUMAN_FACTION_ID));
+        assert_eq!(defense.owner_name, get_faction_name(HUMAN_FACTION_ID).unwrap());
+        assert_eq!(defense.event.map(|event| event.id), Some(5));
+        assert!((defense.progress().unwrap() - 0.75).abs() < 1e-9);
+
+        assert_eq!(CampaignType::from(1), CampaignType::Unknown(1));
+        assert_eq!(CampaignType::from(9), CampaignType::Unknown(9));
+    }
+
+    #[test]
+    fn test_snapshot_planets() {
+        let (war_info, status) = sample_war();
+        let snapshot = WarSnapshot {
+            war_id: 801, language: Language::English, time: status.time, fetched_at: 0, status, war_info,
+            news: Vec::new(), errors: Vec::new(),
+        };
+        assert!(snapshot.is_complete());
+
+        let planets = snapshot.planets();
+        assert_eq!(planets.iter().map(|p| p.index).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        let super_earth = snapshot.planet(0).unwrap();
+        assert_eq!(super_earth.name, "Super Earth");
+        assert_eq!(super_earth.owner, Some(1));
+        assert_eq!(super_earth.owner_name, get_faction_name(1));
+        assert_eq!(super_earth.sector_name, get_sector_name(0));
+        assert_eq!(planets[3].status.map(|ps| ps.players), Some(2000));
+        assert!((planets[3].liberation.unwrap() - 60.0).abs() < 1e-9);
+        assert!(snapshot.planet(99).is_none());
+    }
+
+    #[test]
+    fn test_war_id_cache() {
+        let cache = requests::WarIdCache::default();
+        let now = std::time::Instant::now();
+        assert_eq!(cache.get(now), None);
+        cache.set(801, now);
+        assert_eq!(cache.get(now), Some(801));
+        assert_eq!(cache.get(now + WAR_ID_CACHE_TTL / 2), Some(801));
+        assert_eq!(cache.get(now + WAR_ID_CACHE_TTL), None);
+        assert_eq!(cache.stale(), Some(801));
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_history_store() {
+        use storage::{HistoryStore, OwnerChange};
+
+        let mut store = HistoryStore::open_in_memory().unwrap();
+        let (_, mut status) = sample_war();
+        status.global_events.push(sample_global_event());
+        store.record_status(&status, Language::English, 1000).unwrap();
+        status.time += 100;
+        status.planet_status[3].health = 200000;
+        store.record_status(&status, Language::English, 2000).unwrap();
+        status.planet_status[3].owner = 1;
+        status.planet_status[3].health = 1000000;
+        store.record_status(&status, Language::English, 3000).unwrap();
+
+        let history = store.planet_history(801, 3, 1500).unwrap();
+        assert_eq!(history.iter().map(|s| s.health).collect::<Vec<_>>(), vec![200000, 1000000]);
+        assert_eq!(history[0].war_time, 1100);
+        assert_eq!(store.owner_history(801, 3).unwrap(), vec![
+            OwnerChange { recorded_at: 1000, war_time: 1000, owner: 2 },
+            OwnerChange { recorded_at: 3000, war_time: 1100, owner: 1 },
+        ]);
+        assert_eq!(store.latest_snapshot(801).unwrap(), Some(3000));
+
+        // global events and news are only stored once
+        let events = store.global_events(801, Language::English).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].first_seen, 1000);
+        let news: Vec<NewsItem> = serde_json::from_value(serde_json::json!([
+            {"id": 1, "published": 100, "type": 0, "tagIds": ["MajorOrder"], "message": "Hold."},
+            {"id": 2, "published": 200, "type": 0, "message": "Advance."},
+        ])).unwrap();
+        assert_eq!(store.record_news(801, Language::English, &news).unwrap(), 2);
+        assert_eq!(store.record_news(801, Language::English, &news).unwrap(), 0);
+        let stored = store.news(801, Language::English, 100).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].message, "Advance.");
+        assert_eq!(store.news(801, Language::English, 0).unwrap()[0].tag_ids, vec!["MajorOrder".to_string()]);
+
+        let (war_info, _) = sample_war();
+        let rows = store.planet_rows(&war_info, 2000).unwrap();
+        assert_eq!(rows.len(), 10);
+        assert_eq!((rows[3].recorded_at, rows[3].liberation), (Some(2000), Some(80.0)));
+
+        assert_eq!(store.prune(2500).unwrap(), 2);
+        assert_eq!(store.planet_history(801, 3, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_export_planet_rows() {
+        let (war_info, status) = sample_war();
+        let (_, mut later) = sample_war();
+        later.time = 2000;
+        later.planet_status[3].health = 100000;
+        let rows = planet_rows([(&status, Some(10)), (&later, None)], &war_info);
+        assert_eq!(rows.len(), 10);
+
+        let row = &rows[3];
+        assert_eq!((row.war_time, row.recorded_at, row.planet_index, row.owner), (1000, Some(10), 3, 2));
+        assert_eq!(row.max_health, Some(1000000));
+        assert!((row.liberation.unwrap() - 60.0).abs() < 1e-9);
+        assert_eq!(rows[0].liberation, Some(100.0));
+        assert!((rows[8].liberation.unwrap() - 90.0).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_export_csv() {
+        let (war_info, status) = sample_war();
+        let rows = planet_rows([(&status, None)], &war_info);
+        let mut csv = Vec::new();
+        write_csv(&rows, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next().unwrap(), "war_id,war_time,recorded_at,planet_index,planet_name,sector,owner,owner_name,health,max_health,liberation,players,regen_per_second");
+        assert_eq!(lines.count(), 5);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_export_parquet() {
+        let (war_info, status) = sample_war();
+        let rows = planet_rows([(&status, None)], &war_info);
+        let batch = planet_rows_to_record_batch(&rows).unwrap();
+        assert_eq!((batch.num_rows(), batch.num_columns()), (5, 13));
+
+        let mut parquet = Vec::new();
+        write_parquet(&rows, &mut parquet).unwrap();
+        assert_eq!(&parquet[..4], b"PAR1");
+    }
+
+    #[cfg(feature = "exporter")]
+    #[test]
+    fn test_render_metrics() {
+        let (war_info, status) = sample_war();
+        let metrics = exporter::render_metrics(&status, &war_info, Language::English);
+        assert!(metrics.contains("# TYPE helldivers_planet_players gauge\n"));
+        assert!(metrics.contains("helldivers_players_total{war=\"801\"} 2810\n"));
+        assert!(metrics.contains("helldivers_impact_multiplier{war=\"801\"} 0.05\n"));
+        let super_earth = format!(
+            "helldivers_planet_players{{war=\"801\",planet=\"0\",name=\"Super Earth\",sector=\"{}\"}} 10\n",
+            get_sector_name(0).unwrap(),
+        );
+        assert!(metrics.contains(&super_earth), "{}", metrics);
+        // the owner is a gauge of its own, so a planet changing hands keeps its series
+        assert!(metrics.lines().any(|l| l.starts_with("helldivers_planet_owner{war=\"801\",planet=\"3\"") && l.ends_with(" 2")));
+        assert!(!metrics.contains("owner=\""));
+        assert!(metrics.lines().any(|l| l.starts_with("helldivers_planet_liberation_percent{war=\"801\",planet=\"3\"") && l.ends_with(" 60")));
+        assert!(metrics.lines().any(|l| l.starts_with("helldivers_faction_planets{war=\"801\",faction=\"1\"") && l.ends_with(" 3")));
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = server::RateLimiter::new(2, std::time::Duration::from_secs(60));
+        let start = std::time::Instant::now();
+        assert!(limiter.try_acquire(start));
+        assert!(limiter.try_acquire(start + std::time::Duration::from_secs(10)));
+        assert!(!limiter.try_acquire(start + std::time::Duration::from_secs(20)));
+        assert!(limiter.try_acquire(start + std::time::Duration::from_secs(60)));
+        assert!(!limiter.try_acquire(start + std::time::Duration::from_secs(65)));
+        assert!(!limiter.is_idle(start + std::time::Duration::from_secs(110)));
+        assert!(limiter.is_idle(start + std::time::Duration::from_secs(120)));
+    }
+
+    #[cfg(feature = "server")]
+    #[tokio::test]
+    async fn test_server_routes() {
+        use futures_util::FutureExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static FETCHES: AtomicUsize = AtomicUsize::new(0);
+        let upstream = server::Upstream {
+            current_war_id: || async { Ok(801) }.boxed(),
+            fetch_snapshot: |war_id, language| async move {
+                FETCHES.fetch_add(1, Ordering::SeqCst);
+                let (war_info, status) = sample_war();
+                Ok(WarSnapshot { war_id, language, time: status.time, fetched_at: 1706041313, status, war_info, news: Vec::new(), errors: Vec::new() })
+            }.boxed(),
+        };
+        let config = server::ServerConfig { watch_interval: None, max_requests_per_client_per_minute: 4, ..server::ServerConfig::default() };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(server::serve_router(listener, server::router_with(config, upstream)));
+
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let get = |path: &str| client.get(format!("http://{}{}", addr, path)).send();
+        let planet: serde_json::Value = get("/war/801/planets/3").await.unwrap().json().await.unwrap();
+        assert_eq!((planet["owner"].as_i64(), planet["liberation"].as_f64()), (Some(2), Some(60.0)));
+        assert_eq!(get("/war/801/status").await.unwrap().status(), 200);
+        assert_eq!(FETCHES.load(Ordering::SeqCst), 1);
+
+        // only the current war and the few before it are fetched
+        assert_eq!(get("/war/802/status").await.unwrap().status(), 404);
+        assert_eq!(get("/war/700/status").await.unwrap().status(), 404);
+        assert_eq!(FETCHES.load(Ordering::SeqCst), 1);
+
+        // the fifth request is over the limit of the client
+        assert_eq!(get("/planets/3").await.unwrap().status(), 429);
+    }
+
+    #[test]
+    fn test_status_watcher() {
+        let news = |items: &[(i64, i64)]| -> Vec<NewsItem> {
+            let items: Vec<serde_json::Value> = items.iter()
+                .map(|(id, published)| serde_json::json!({"id": id, "published": published, "type": 0, "message": "News"}))
+                .collect();
+            serde_json::from_value(serde_json::Value::Array(items)).unwrap()
+        };
+        let mut watcher = StatusWatcher::new(801, Language::English);
+
+        // the first poll only records the state of the war
+        let (_, mut status) = sample_war();
+        status.global_events.push(sample_global_event());
+        add_defense_campaign(&mut status);
+        let mut cursor = NewsCursor::default();
+        let items = cursor.advance(news(&[(1, 100)]));
+        assert!(watcher.update(status, items, cursor).is_empty());
+
+        let (_, mut status) = sample_war();
+        status.global_events.push(sample_global_event());
+        add_defense_campaign(&mut status);
+        status.planet_status[3].owner = 1;
+        status.campaigns.remove(0);
+        status.campaigns[0].r#type = 1;
+        status.campaigns.push(serde_json::from_value(serde_json::json!({"id": 9, "planetIndex": 4, "type": 0, "count": 1})).unwrap());
+        // a new campaign with a planet event is the defense of a human planet
+        status.campaigns.push(serde_json::from_value(serde_json::json!({"id": 10, "planetIndex": 1, "type": 0, "count": 1})).unwrap());
+        status.planet_events.push(serde_json::from_value(serde_json::json!({
+            "id": 6, "planetIndex": 1, "eventType": 1, "race": 2, "health": 500000, "maxHealth": 1000000,
+            "startTime": 1500, "expireTime": 90000, "campaignId": 10, "jointOperationIds": []
+        })).unwrap());
+        status.global_events[0].event_id = 2;
+        let items = cursor.advance(news(&[(1, 100), (2, 200)]));
+        let changes = watcher.update(status, items, cursor);
+        assert_eq!(changes, vec![
+            WarChange::PlanetFlipped { planet: 3, name: String::new(), from: 2, to: 1 },
+            WarChange::CampaignStarted { campaign_id: 9, planet: 4, name: String::new(), campaign_type: CampaignType::Liberation },
+            WarChange::CampaignStarted { campaign_id: 10, planet: 1, name: String::new(), campaign_type: CampaignType::Defense },
+            WarChange::CampaignEnded { campaign_id: 1, planet: 3, name: String::new() },
+            WarChange::GlobalEventStarted { event_id: 2, title: "BRIEFING".to_string(), message: "Hold the line on <i=1>Pathfinder V</i>.".to_string() },
+            WarChange::NewsPublished { id: 2, published: 200, message: "News".to_string() },
+        ]);
+        assert_eq!(serde_json::to_value(&changes[0]).unwrap()["type"], "planet_flipped");
+        assert_eq!(watcher.status().map(|s| s.campaigns.len()), Some(3));
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_event_hub() {
+        let hub = server::EventHub::new(2);
+        let change = |planet: i64| WarChange::PlanetFlipped { planet, name: String::new(), from: 2, to: 1 };
+        let ids: Vec<u64> = (0..3).map(|planet| hub.publish(801, change(planet)).id).collect();
+        // IDs continue from the start of the hub, so a restarted server does not reuse them
+        assert!(ids[0] > 1_700_000_000_000);
+        assert_eq!(ids, vec![ids[0], ids[0] + 1, ids[0] + 2]);
+        let replayed = |subscription: &server::Subscription| subscription.replay.iter().map(|e| e.id).collect::<Vec<_>>();
+
+        // only the latest events are kept for replay, clients that missed older ones are reset
+        let subscription = hub.subscribe(Some(ids[0] - 1));
+        assert_eq!((subscription.reset, replayed(&subscription)), (Some(ids[0]), ids[1..].to_vec()));
+        let subscription = hub.subscribe(Some(ids[0]));
+        assert_eq!((subscription.reset, replayed(&subscription)), (None, ids[1..].to_vec()));
+        let subscription = hub.subscribe(None);
+        assert!(subscription.reset.is_none() && subscription.replay.is_empty());
+        // IDs from a later server, e.g. with a clock ahead of this one, are reset too
+        assert_eq!(hub.subscribe(Some(ids[2] + 1)).reset, Some(ids[0]));
+
+        let mut subscription = hub.subscribe(Some(ids[1]));
+        assert_eq!((subscription.reset, replayed(&subscription)), (None, vec![ids[2]]));
+        hub.publish(801, change(4));
+        let event = subscription.receiver.try_recv().unwrap();
+        assert_eq!((event.id, event.war_id), (ids[2] + 1, 801));
+        assert_eq!(serde_json::to_value(&*event).unwrap()["type"], "planet_flipped");
+    }
+
+    #[test]
+    fn test_feed_dates() {
+        assert_eq!(feed::rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(feed::rfc3339(1706040313), "2024-01-23T20:05:13Z");
+        assert_eq!(feed::rfc2822(951782400), "Tue, 29 Feb 2000 00:00:00 +0000");
+        assert_eq!(feed::rfc2822(0), "Thu, 01 Jan 1970 00:00:00 +0000");
+
+        let (war_info, _) = sample_war();
+        assert_eq!(WarClock::from_war_info(&war_info).unix(1000), 1706041313);
+        assert_eq!(WarClock::from_war_time(1000, 1706041313), WarClock { start: 1706040313 });
+    }
+
+    #[test]
+    fn test_news_feeds() {
+        let (war_info, mut status) = sample_war();
+        status.global_events.push(sample_global_event());
+        let news: Vec<NewsItem> = serde_json::from_value(serde_json::json!([
+            {"id": 7, "published": 500, "type": 0, "message": "Liberate <i=1>Malevelon Creek</i> & hold.\nMore orders follow."},
+            {"id": 8, "published": 2000, "type": 0, "message": "Stand by."},
+        ])).unwrap();
+        let clock = WarClock::from_war_info(&war_info);
+        let config = FeedConfig::new(801, "https://example.com/feed.xml");
+        // the global event keeps the date it was first seen at, rather than following the Status
+        let mut dates = GlobalEventDates::default();
+        dates.record(&status);
+        status.time = 2500;
+        dates.record(&status);
+        assert_eq!(dates.first_seen(1), Some(1000));
+
+        let atom = atom_feed(&news, Some(&status), &dates, &clock, &config);
+        assert!(atom.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\""));
+        assert!(atom.contains("<updated>2024-01-23T20:38:33Z</updated>"));
+        assert!(atom.contains("<id>urn:helldivers:war:801:news:7</id>"));
+        assert!(atom.contains("<title>Liberate Malevelon Creek &amp; hold.</title>"));
+        assert!(atom.contains("<content type=\"html\">Liberate &lt;span class=&quot;highlight highlight-1&quot;&gt;Malevelon Creek&lt;/span&gt; &amp;amp; hold.&lt;br&gt;\nMore orders follow.</content>"));
+        assert!(atom.contains("<title>BRIEFING</title>"));
+        assert_eq!(atom.matches("<entry>").count(), 3);
+        // newest first, with the global event dated when it was first seen
+        let order: Vec<usize> = ["news:8", "event:1", "news:7"].iter().map(|id| atom.find(id).unwrap()).collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+
+        let config = FeedConfig { html: false, ..config };
+        let rss = rss_feed(&news, None, &dates, &clock, &config);
+        assert!(rss.contains("<guid isPermaLink=\"false\">urn:helldivers:war:801:news:8</guid>"));
+        assert!(rss.contains("<pubDate>Tue, 23 Jan 2024 20:13:33 +0000</pubDate>"));
+        assert!(rss.contains("<description>Liberate Malevelon Creek &amp; hold.\nMore orders follow.</description>"));
+        assert!(rss.contains("<lastBuildDate>Tue, 23 Jan 2024 20:38:33 +0000</lastBuildDate>"));
+        assert_eq!(rss.matches("<item>").count(), 2);
+        assert!(rss.trim_end().ends_with("</rss>"));
+    }
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 use std::collections::hash_map::HashMap;
+use serde::Deserialize;
 use toml::from_str;
 
-use crate::models::{Planet, Faction, Sector};
+use crate::models::{Planet, Biome, Hazard, Faction, Sector, SectorConsistency};
+use crate::{BIOMES, HAZARDS, PLANET_SECTORS, SECTORS};
 
 
 const RAW_PLANETS: &str = include_str!("../res/planets.toml");
@@ -10,28 +12,141 @@ const RAW_PLANETS: &str = include_str!("../res/planets.toml");
 // ToDo: Implement effects once their structure is known
 const RAW_FACTIONS: &str = include_str!("../res/factions.toml");
 const RAW_SECTORS: &str = include_str!("../res/sectors.toml");
+const RAW_BIOMES: &str = include_str!("../res/biomes.toml");
+const RAW_HAZARDS: &str = include_str!("../res/hazards.toml");
+
+/// A planet in a planet table, either just its name or its name with details
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum PlanetEntry {
+    Name(String),
+    Details {
+        name: String,
+        biome: Option<String>,
+        #[serde(default)]
+        hazards: Vec<String>,
+        description: Option<String>,
+        #[serde(default)]
+        names: HashMap<String, String>,
+    },
+}
+
+impl PlanetEntry {
+    /// Build the planet, resolving its biome and hazards against the bundled tables
+    pub fn into_planet(self, id: i64) -> Result<Planet, String> {
+        match self {
+            PlanetEntry::Name(name) => Ok(Planet { id, name, ..Default::default() }),
+            PlanetEntry::Details { name, biome, hazards, description, names } => {
+                let biome = match biome {
+                    Some(slug) => Some(BIOMES.get(&slug).cloned().ok_or(format!("Unknown biome {} for planet {}", slug, id))?),
+                    None => None,
+                };
+                let hazards = hazards.iter()
+                    .map(|slug| HAZARDS.get(slug).cloned().ok_or(format!("Unknown hazard {} for planet {}", slug, id)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Planet { id, name, sector: None, biome, hazards, description, names })
+            }
+        }
+    }
+}
+
+/// A faction in a faction table, either just its name or its name with translations
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum FactionEntry {
+    Name(String),
+    Details {
+        name: String,
+        #[serde(default)]
+        names: HashMap<String, String>,
+    },
+}
+
+impl FactionEntry {
+    pub fn into_faction(self, id: i64) -> Faction {
+        match self {
+            FactionEntry::Name(name) => Faction { id, name, names: HashMap::new() },
+            FactionEntry::Details { name, names } => Faction { id, name, names },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Description {
+    name: String,
+    description: String,
+}
 
 pub fn load_planets() -> HashMap<i64, Planet> {
-    let planets: HashMap<String, String> = from_str(RAW_PLANETS).unwrap();
+    let planets: HashMap<String, PlanetEntry> = from_str(RAW_PLANETS).unwrap();
     let mut planet_map: HashMap<i64, Planet> = HashMap::new();
-    for (id, name) in planets {
-        planet_map.insert(id.parse().unwrap(), Planet{id: id.parse().unwrap(), name});
+    for (id, entry) in planets {
+        let id = id.parse().unwrap();
+        let mut planet = entry.into_planet(id).unwrap();
+        planet.sector = PLANET_SECTORS.get(&id).copied();
+        planet_map.insert(id, planet);
     }
     planet_map
 }
 
+pub fn load_biomes() -> HashMap<String, Biome> {
+    let biomes: HashMap<String, Description> = from_str(RAW_BIOMES).unwrap();
+    biomes.into_iter()
+        .map(|(slug, d)| (slug.clone(), Biome { slug, name: d.name, description: d.description }))
+        .collect()
+}
+
+pub fn load_hazards() -> HashMap<String, Hazard> {
+    let hazards: HashMap<String, Description> = from_str(RAW_HAZARDS).unwrap();
+    hazards.into_iter()
+        .map(|(slug, d)| (slug.clone(), Hazard { slug, name: d.name, description: d.description }))
+        .collect()
+}
+
 pub fn load_factions() -> HashMap<i64, Faction> {
-    let factions: HashMap<String, String> = from_str(RAW_FACTIONS).unwrap();
+    let factions: HashMap<String, FactionEntry> = from_str(RAW_FACTIONS).unwrap();
     let mut faction_map: HashMap<i64, Faction> = HashMap::new();
-    for (id, name) in factions {
-        faction_map.insert(id.parse().unwrap(), Faction{id: id.parse().unwrap(), name});
+    for (id, entry) in factions {
+        let id = id.parse().unwrap();
+        faction_map.insert(id, entry.into_faction(id));
     }
     faction_map
 }
 
 pub fn load_sectors() -> HashMap<i64, Sector> {
+    parse_sectors().into_iter().map(|sector| (sector.id, sector)).collect()
+}
+
+/// Map each planet to the ID of the sector containing it
+pub fn load_planet_sectors() -> HashMap<i64, i64> {
+    SECTORS.values().flat_map(|sector| sector.planets.iter().map(|planet| (*planet, sector.id))).collect()
+}
+
+/// Check the bundled sector table against the bundled planet table
+pub fn check_sectors(planets: &HashMap<i64, Planet>) -> SectorConsistency {
+    let mut planet_sectors: HashMap<i64, Vec<i64>> = HashMap::new();
+    let mut sector_names: HashMap<i64, Vec<String>> = HashMap::new();
+    for sector in parse_sectors() {
+        for planet in &sector.planets {
+            planet_sectors.entry(*planet).or_default().push(sector.id);
+        }
+        sector_names.entry(sector.id).or_default().push(sector.name);
+    }
+
+    let mut no_sector: Vec<i64> = planets.keys().filter(|id| !planet_sectors.contains_key(id)).copied().collect();
+    no_sector.sort();
+    planet_sectors.retain(|_, sectors| sectors.len() > 1);
+    sector_names.retain(|_, names| names.len() > 1);
+    for names in sector_names.values_mut() {
+        names.sort();
+    }
+
+    SectorConsistency { multiple_sectors: planet_sectors, no_sector, duplicate_sector_ids: sector_names }
+}
+
+pub fn parse_sectors() -> Vec<Sector> {
     let sectors: HashMap<String, toml::Value> = from_str(RAW_SECTORS).unwrap();
-    let mut sector_map: HashMap<i64, Sector> = HashMap::new();
+    let mut sector_list: Vec<Sector> = Vec::new();
 
     for (name, value) in sectors {
         let id = value.get("id").unwrap().as_integer().unwrap();
@@ -39,9 +154,14 @@ pub fn load_sectors() -> HashMap<i64, Sector> {
             .iter()
             .map(|v| v.as_integer().unwrap())
             .collect();
+        let names = value.get("names")
+            .map(|names| names.as_table().unwrap().iter()
+                .map(|(language, name)| (language.clone(), name.as_str().unwrap().to_string()))
+                .collect())
+            .unwrap_or_default();
 
-        sector_map.insert(id, Sector { id, name, planets });
+        sector_list.push(Sector { id, name, planets, names });
     }
 
-    sector_map
+    sector_list
 }
\ No newline at end of file
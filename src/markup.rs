@@ -0,0 +1,164 @@
+use serde::Serialize;
+
+/// A piece of in-game text
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum MarkupNode {
+    Text(String),
+    /// Text wrapped in a `<i=N>...</i>` tag, which the game draws highlighted
+    Highlight { style: i64, children: Vec<MarkupNode> },
+    LineBreak,
+}
+
+/// In-game text parsed from the markup used in news items and global events
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Markup {
+    pub nodes: Vec<MarkupNode>,
+}
+
+/// Parse in-game text such as `Liberate <i=1>Malevelon Creek</i>.`
+///
+/// Unclosed highlights run to the end of the text, stray closing tags are dropped,
+/// and anything else that looks like a tag is kept as text.
+///
+/// Arguments:
+///   text: &str - The text of a news item or global event
+pub fn parse_markup(text: &str) -> Markup {
+    // each open highlight keeps its style and the nodes of the enclosing level
+    let mut stack: Vec<(i64, Vec<MarkupNode>)> = Vec::new();
+    let mut nodes: Vec<MarkupNode> = Vec::new();
+    let mut text_buffer = String::new();
+
+    let mut rest = text;
+    while !rest.is_empty() {
+        if let Some((style, len)) = opening_tag(rest) {
+            flush(&mut text_buffer, &mut nodes);
+            stack.push((style, std::mem::take(&mut nodes)));
+            rest = &rest[len..];
+        } else if rest.starts_with("</i>") {
+            flush(&mut text_buffer, &mut nodes);
+            if let Some((style, parent)) = stack.pop() {
+                let children = std::mem::replace(&mut nodes, parent);
+                nodes.push(MarkupNode::Highlight { style, children });
+            }
+            rest = &rest["</i>".len()..];
+        } else if rest.starts_with("\r\n") || rest.starts_with('\n') {
+            flush(&mut text_buffer, &mut nodes);
+            nodes.push(MarkupNode::LineBreak);
+            rest = &rest[if rest.starts_with('\r') { 2 } else { 1 }..];
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            text_buffer.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    flush(&mut text_buffer, &mut nodes);
+    while let Some((style, parent)) = stack.pop() {
+        let children = std::mem::replace(&mut nodes, parent);
+        nodes.push(MarkupNode::Highlight { style, children });
+    }
+    Markup { nodes }
+}
+
+/// Match a `<i=N>` tag at the start of the text, returning its style and length
+fn opening_tag(text: &str) -> Option<(i64, usize)> {
+    let inner = text.strip_prefix("<i=")?;
+    let end = inner.find('>')?;
+    let style = inner[..end].trim().parse().ok()?;
+    Some((style, "<i=".len() + end + 1))
+}
+
+fn flush(text: &mut String, nodes: &mut Vec<MarkupNode>) {
+    if !text.is_empty() {
+        nodes.push(MarkupNode::Text(std::mem::take(text)));
+    }
+}
+
+impl Markup {
+    /// The text without any highlighting
+    pub fn to_plain_text(&self) -> String {
+        self.render(&|text| text.to_string(), &|_, inner, _| inner, "\n")
+    }
+
+    /// Markdown as used by Discord, with highlights in bold
+    pub fn to_markdown(&self) -> String {
+        // markdown cannot nest bold text, so only the outermost highlight is marked
+        self.render(&escape_markdown, &|_, inner, nested| {
+            if nested || inner.trim().is_empty() {
+                return inner;
+            }
+            // bold text may not start or end with whitespace, so it goes outside the markers
+            let trimmed = inner.trim();
+            let start = inner.len() - inner.trim_start().len();
+            format!("{}**{}**{}", &inner[..start], trimmed, &inner[start + trimmed.len()..])
+        }, "\n")
+    }
+
+    /// HTML, with highlights as `<span class="highlight highlight-N">` elements
+    pub fn to_html(&self) -> String {
+        self.render(&escape_html, &|style, inner, _| format!(r#"<span class="highlight highlight-{}">{}</span>"#, style, inner), "<br>\n")
+    }
+
+    /// Text for a terminal, with highlights in bold yellow
+    pub fn to_ansi(&self) -> String {
+        // nested highlights end with a reset, so restore the style of the enclosing highlight afterwards
+        fn render(nodes: &[MarkupNode], active: bool, out: &mut String) {
+            for node in nodes {
+                match node {
+                    MarkupNode::Text(text) => out.push_str(text),
+                    MarkupNode::LineBreak => out.push('\n'),
+                    MarkupNode::Highlight { children, .. } => {
+                        out.push_str(ANSI_HIGHLIGHT);
+                        render(children, true, out);
+                        out.push_str(ANSI_RESET);
+                        if active {
+                            out.push_str(ANSI_HIGHLIGHT);
+                        }
+                    }
+                }
+            }
+        }
+        let mut out = String::new();
+        render(&self.nodes, false, &mut out);
+        out
+    }
+
+    /// Render the nodes, calling `highlight` with the style, the rendered children and whether it is nested
+    fn render(&self, text: &Renderer, highlight: &HighlightRenderer, line_break: &str) -> String {
+        fn render(nodes: &[MarkupNode], text: &Renderer, highlight: &HighlightRenderer, line_break: &str, nested: bool) -> String {
+            nodes.iter().map(|node| match node {
+                MarkupNode::Text(value) => text(value),
+                MarkupNode::LineBreak => line_break.to_string(),
+                MarkupNode::Highlight { style, children } => {
+                    highlight(*style, render(children, text, highlight, line_break, true), nested)
+                }
+            }).collect()
+        }
+        render(&self.nodes, text, highlight, line_break, false)
+    }
+}
+
+type Renderer = dyn Fn(&str) -> String;
+type HighlightRenderer = dyn Fn(i64, String, bool) -> String;
+
+const ANSI_HIGHLIGHT: &str = "\x1b[1;33m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
@@ -3,7 +3,7 @@ mod war_info;
 mod wartime;
 mod news_feed;
 
-pub use status::{Status, PlanetStatus, PlanetAttack, Campaign, GlobalEvent};
+pub use status::{Status, PlanetStatus, PlanetAttack, Campaign, PlanetEvent, GlobalEvent};
 pub use war_info::{WarInfo, HomeWorld, Position, PlanetInfo};
-pub use wartime::WarTime;
+pub use wartime::{WarTime, WarId};
 pub use news_feed::NewsItem;
\ No newline at end of file
@@ -1,16 +1,40 @@
 use std::collections::HashMap;
-use serde::{Deserialize};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct NewsItem {
     pub id: i64,
     pub published: i64,
     #[serde(rename = "type")]
     pub news_type: i64,  // always 0 so far
-    #[serde(default)]
+    // the API sends `tagIds`, and `tag_ids` is still accepted for JSON written before the rename
+    #[serde(default, rename = "tagIds", alias = "tag_ids")]
     pub tag_ids: Vec<String>,
     pub message: String,
     #[serde(flatten)]
     pub unknown: HashMap<String, Value>,
-}
\ No newline at end of file
+}
+
+impl NewsItem {
+    /// Parse the in-game markup of the message
+    pub fn markup(&self) -> crate::Markup {
+        crate::parse_markup(&self.message)
+    }
+
+    pub fn news_type(&self) -> crate::NewsType {
+        crate::NewsType::from(self.news_type)
+    }
+
+    pub fn tags(&self) -> Vec<crate::NewsTag> {
+        self.tag_ids.iter().map(|tag| crate::NewsTag::from(tag.as_str())).collect()
+    }
+
+    /// What the news item is about, see `classify_news`
+    ///
+    /// Arguments:
+    ///   language: Language - The language the news item was fetched in
+    pub fn category(&self, language: crate::Language) -> crate::NewsCategory {
+        crate::classify_news(self, language)
+    }
+}
@@ -1,8 +1,8 @@
 use std::collections::HashMap;
-use serde::{Deserialize};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Status {
     #[serde(rename = "warId")]
     pub war_id: i64,
@@ -18,7 +18,8 @@ pub struct Status {
     pub campaigns: Vec<Campaign>,
     // pub community_targets: Vec<String>, // ToDo: Don't know the structure of community_targets yet
     // pub joint_operations: Vec<String>,  // ToDo: Don't know the structure of joint_operations yet
-    // pub planet_events: Vec<String>,  // ToDo: Don't know the structure of planet_events yet
+    #[serde(rename = "planetEvents", default)]
+    pub planet_events: Vec<PlanetEvent>,
     // pub planet_active_effects: Vec<String>,  // ToDo: Don't know the structure of planet_active_effects yet
     // pub active_election_policy_effects: Vec<String>,  // ToDo: Don't know the structure of active_election_policy_effects yet
     #[serde(rename = "globalEvents")]
@@ -28,7 +29,7 @@ pub struct Status {
     pub unknown: HashMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct GlobalEvent {
     #[serde(rename = "eventId")]
     pub event_id: i64,
@@ -46,12 +47,13 @@ pub struct GlobalEvent {
     #[serde(rename = "assignmentId32")]
     pub assignment_id32: i64,
     // pub effect_ids: Vec<String>, // ToDo: Don't know the structure of effect_ids yet
-    // pub planet_indices: Vec<String>, // ToDo: Don't know the structure of planet_indices yet
+    #[serde(rename = "planetIndices", default)]
+    pub planet_indices: Vec<i64>,
     #[serde(flatten)]
     pub unknown: HashMap<String, Value>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Campaign {
     pub id: i64,
     #[serde(rename = "planetIndex")]
@@ -60,24 +62,48 @@ pub struct Campaign {
     pub count: i64,
     #[serde(flatten)]
     pub unknown: HashMap<String, Value>,
-    #[serde(skip)]
+    #[serde(skip_deserializing, rename = "planetName")]
     pub planet_name: String,
 }
 
-#[derive(Deserialize, Debug)]
+/// An event on a planet, such as an enemy attack that has to be defended against
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PlanetEvent {
+    pub id: i64,
+    #[serde(rename = "planetIndex")]
+    pub planet_index: i64,
+    #[serde(rename = "eventType")]
+    pub event_type: i64,
+    pub race: i64,
+    pub health: i64,
+    #[serde(rename = "maxHealth")]
+    pub max_health: i64,
+    #[serde(rename = "startTime")]
+    pub start_time: i64,
+    #[serde(rename = "expireTime")]
+    pub expire_time: i64,
+    #[serde(rename = "campaignId")]
+    pub campaign_id: i64,
+    #[serde(rename = "jointOperationIds", default)]
+    pub joint_operation_ids: Vec<i64>,
+    #[serde(flatten)]
+    pub unknown: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct PlanetAttack {
     pub source: i64,
     pub target: i64,
     #[serde(flatten)]
     pub unknown: HashMap<String, Value>,
 
-    #[serde(skip)]
+    #[serde(skip_deserializing, rename = "sourceName")]
     pub source_name: String,
-    #[serde(skip)]
+    #[serde(skip_deserializing, rename = "targetName")]
     pub target_name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct PlanetStatus {
     pub index: i64,
     pub owner: i64,
@@ -87,7 +113,49 @@ pub struct PlanetStatus {
     pub players: i64,
     #[serde(flatten)]
     pub unknown: HashMap<String, Value>,
-    #[serde(skip)]
+    #[serde(skip_deserializing, rename = "planetName")]
     pub planet_name: String,
 }
 
+impl GlobalEvent {
+    /// Parse the in-game markup of the title
+    pub fn title_markup(&self) -> crate::Markup {
+        crate::parse_markup(&self.title)
+    }
+
+    /// Parse the in-game markup of the message
+    pub fn message_markup(&self) -> crate::Markup {
+        crate::parse_markup(&self.message)
+    }
+}
+
+impl Status {
+    /// The planet event of a campaign, matched by campaign ID or else by planet
+    pub fn campaign_event(&self, campaign: &Campaign) -> Option<&PlanetEvent> {
+        self.planet_events.iter()
+            .find(|event| event.campaign_id == campaign.id)
+            .or_else(|| self.planet_events.iter().find(|event| event.planet_index == campaign.planet_index))
+    }
+
+    /// The type of a campaign, telling defense apart from liberation by whether its planet is under attack
+    pub fn campaign_type(&self, campaign: &Campaign) -> crate::CampaignType {
+        match campaign.campaign_type() {
+            crate::CampaignType::Liberation if self.campaign_event(campaign).is_some() => crate::CampaignType::Defense,
+            campaign_type => campaign_type,
+        }
+    }
+}
+
+impl Campaign {
+    /// The type of the campaign, without telling defense apart from liberation, see `Status::campaign_type`
+    pub fn campaign_type(&self) -> crate::CampaignType {
+        crate::CampaignType::from(self.r#type)
+    }
+}
+
+impl PlanetStatus {
+    /// Get the planet, including its biome and environmental hazards
+    pub fn planet(&self) -> Option<crate::Planet> {
+        crate::get_planet(self.index)
+    }
+}
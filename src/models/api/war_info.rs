@@ -54,9 +54,15 @@ pub struct PlanetInfo {
     pub disabled: bool,
     #[serde(rename = "initialOwner")]
     pub initial_owner: i64,
-    #[serde(skip)]
+    #[serde(skip_deserializing, rename = "planetName")]
     pub planet_name: String,
     #[serde(flatten)]
     pub unknown: HashMap<String, Value>,
 }
 
+impl PlanetInfo {
+    /// Get the planet, including its biome and environmental hazards
+    pub fn planet(&self) -> Option<crate::Planet> {
+        crate::get_planet(self.index)
+    }
+}
@@ -1,6 +1,11 @@
-use serde::{Deserialize};
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct WarTime {
     pub time: i64, // note, seems to only update every 10 seconds
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WarId {
+    pub id: i64,
+}
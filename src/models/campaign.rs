@@ -0,0 +1,72 @@
+use serde::{Serialize, Serializer};
+
+use crate::models::api::{Campaign, PlanetEvent, PlanetInfo, PlanetStatus};
+
+/// The `type` of a campaign
+///
+/// Defense campaigns share type 0 with liberation campaigns, and are told apart by the planet event on the planet.
+/// Only type 0 has been confirmed, so every other type is `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CampaignType {
+    Liberation,
+    Defense,
+    Unknown(i64),
+}
+
+impl From<i64> for CampaignType {
+    fn from(value: i64) -> Self {
+        match value {
+            0 => CampaignType::Liberation,
+            other => CampaignType::Unknown(other),
+        }
+    }
+}
+
+impl CampaignType {
+    pub fn name(&self) -> &str {
+        match self {
+            CampaignType::Liberation => "Liberation",
+            CampaignType::Defense => "Defense",
+            CampaignType::Unknown(_) => "Unknown",
+        }
+    }
+}
+
+impl Serialize for CampaignType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// A campaign joined with everything known about its planet
+#[derive(Debug, Serialize)]
+pub struct CampaignView<'a> {
+    pub campaign: &'a Campaign,
+    pub campaign_type: CampaignType,
+    pub status: Option<&'a PlanetStatus>,
+    pub info: Option<&'a PlanetInfo>,
+    /// The planet event of a defense campaign
+    pub event: Option<&'a PlanetEvent>,
+    /// The faction owning the planet
+    pub owner: Option<i64>,
+    /// The name of the faction owning the planet
+    pub owner_name: String,
+    /// The enemy faction, the attacker for a defense campaign and the owner otherwise
+    pub race: Option<i64>,
+    /// The name of the enemy faction
+    pub race_name: String,
+    pub sector: Option<i64>,
+}
+
+impl CampaignView<'_> {
+    /// How far the campaign has progressed, from 0.0 to 1.0
+    ///
+    /// Liberation progress is the health the planet has lost, defense progress is the health the event has lost.
+    pub fn progress(&self) -> Option<f64> {
+        if let Some(event) = self.event {
+            return (event.max_health > 0).then(|| 1.0 - event.health as f64 / event.max_health as f64);
+        }
+        let (status, info) = (self.status?, self.info?);
+        (info.max_health > 0).then(|| 1.0 - status.health as f64 / info.max_health as f64)
+    }
+}
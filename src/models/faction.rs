@@ -1,4 +1,18 @@
+use std::collections::HashMap;
+
+use crate::models::Language;
+
+#[derive(Debug, Clone)]
 pub struct Faction{
     pub id: i64,
     pub name: String,
+    /// The name in other languages, by locale code such as `de-DE`
+    pub names: HashMap<String, String>,
+}
+
+impl Faction {
+    /// Get the name in a language, falling back to the English name
+    pub fn localized_name(&self, language: Language) -> &str {
+        self.names.get(language.to_str()).unwrap_or(&self.name)
+    }
 }
\ No newline at end of file
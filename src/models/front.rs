@@ -0,0 +1,17 @@
+use crate::models::api::{Campaign, PlanetAttack, PlanetInfo, PlanetStatus};
+
+/// The faction ID of humanity (Super Earth)
+pub const HUMAN_FACTION_ID: i64 = 1;
+
+/// A human-owned planet that borders enemy territory
+#[derive(Debug)]
+pub struct FrontPlanet<'a> {
+    pub status: &'a PlanetStatus,
+    pub info: &'a PlanetInfo,
+    /// Indices of the neighbouring planets owned by the enemy faction of this front
+    pub enemy_neighbours: Vec<i64>,
+    /// Active attacks that target this planet or are launched from it
+    pub attacks: Vec<&'a PlanetAttack>,
+    /// Active campaigns on this planet or on its enemy neighbours
+    pub campaigns: Vec<&'a Campaign>,
+}
@@ -1,7 +1,15 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::HelldiversError;
 
 /// Languages supported by the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[non_exhaustive]
 pub enum Language {
+    #[default]
     English,
     German,
     Spanish,
@@ -12,6 +20,33 @@ pub enum Language {
     Chinese
 }
 
+const ALL: [Language; 8] = [
+    Language::English,
+    Language::German,
+    Language::Spanish,
+    Language::Russian,
+    Language::French,
+    Language::Italian,
+    Language::Polish,
+    Language::Chinese,
+];
+
+/// Unsupported languages and the supported language their speakers are most likely to read
+const RELATED: [(&str, Language); 12] = [
+    ("ca", Language::Spanish),
+    ("gl", Language::Spanish),
+    ("eu", Language::Spanish),
+    ("pt", Language::Spanish),
+    ("uk", Language::Russian),
+    ("be", Language::Russian),
+    ("kk", Language::Russian),
+    ("lb", Language::German),
+    ("gsw", Language::German),
+    ("oc", Language::French),
+    ("co", Language::Italian),
+    ("szl", Language::Polish),
+];
+
 impl Language {
     pub fn to_str(&self) -> &str {
         match self {
@@ -25,4 +60,108 @@ impl Language {
             Language::Chinese => "zh-Hans",
         }
     }
+
+    /// The English name of the language
+    pub fn name(&self) -> &str {
+        match self {
+            Language::English => "English",
+            Language::German => "German",
+            Language::Spanish => "Spanish",
+            Language::Russian => "Russian",
+            Language::French => "French",
+            Language::Italian => "Italian",
+            Language::Polish => "Polish",
+            Language::Chinese => "Chinese",
+        }
+    }
+
+    /// The primary language subtag, e.g. `de` for `de-DE`
+    fn primary_subtag(&self) -> &str {
+        self.to_str().split('-').next().unwrap_or_default()
+    }
+
+    /// Every supported language
+    pub fn all() -> impl Iterator<Item = Language> {
+        ALL.into_iter()
+    }
+
+    /// Find the supported language closest to a locale tag such as `de-AT` or `pt-BR`
+    ///
+    /// Tries the exact locale, then the primary language of the tag, then a related language.
+    pub fn from_locale(tag: &str) -> Option<Language> {
+        Language::from_supported_locale(tag).or_else(|| Language::from_related_locale(tag))
+    }
+
+    /// Match a locale tag exactly, or by its primary language
+    fn from_supported_locale(tag: &str) -> Option<Language> {
+        let tag = tag.trim().replace('_', "-");
+        if let Some(language) = Language::all().find(|l| l.to_str().eq_ignore_ascii_case(&tag)) {
+            return Some(language);
+        }
+        let primary = tag.split('-').next().unwrap_or_default();
+        Language::all().find(|l| l.primary_subtag().eq_ignore_ascii_case(primary))
+    }
+
+    /// Match a locale tag to the supported language related to its primary language
+    fn from_related_locale(tag: &str) -> Option<Language> {
+        let primary = tag.trim().split(['-', '_']).next().unwrap_or_default();
+        RELATED.iter().find(|(related, _)| related.eq_ignore_ascii_case(primary)).map(|(_, language)| *language)
+    }
+
+    /// Pick the language to use for an HTTP `Accept-Language` header
+    ///
+    /// Locales are tried in order of their q-value, first by exact locale or primary language,
+    /// and only if none of them matches, by related language. If nothing matches, English is used.
+    ///
+    /// Arguments:
+    ///   header: &str - The header value, e.g. `de-CH, fr;q=0.8, *;q=0.1`
+    pub fn from_accept_language(header: &str) -> Language {
+        let mut locales: Vec<(&str, f32)> = header.split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // a stable sort keeps the header order for equal q-values
+        locales.sort_by(|a, b| b.1.total_cmp(&a.1));
+        locales.iter().find_map(|(tag, _)| Language::from_supported_locale(tag))
+            .or_else(|| locales.iter().find_map(|(tag, _)| Language::from_related_locale(tag)))
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
+impl FromStr for Language {
+    type Err = HelldiversError;
+
+    /// Parse a locale such as `de` or `de-DE`, or an English name such as `German`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let primary = value.split(['-', '_']).next().unwrap_or_default();
+        Language::all()
+            .find(|l| l.name().eq_ignore_ascii_case(value) || l.primary_subtag().eq_ignore_ascii_case(primary))
+            .ok_or_else(|| HelldiversError::InvalidLanguage(s.to_string()))
+    }
+}
+
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
 }
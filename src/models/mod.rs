@@ -3,8 +3,16 @@ mod planet;
 mod faction;
 mod sector;
 mod languages;
+mod front;
+mod translations;
+mod news;
+mod campaign;
 
-pub use planet::Planet;
+pub use planet::{Planet, Biome, Hazard};
 pub use faction::Faction;
-pub use sector::{Sector};
-pub use languages::Language;
\ No newline at end of file
+pub use sector::{Sector, SectorSummary, SectorConsistency};
+pub use languages::Language;
+pub use front::{FrontPlanet, HUMAN_FACTION_ID};
+pub use translations::{Translations, TranslatedNewsItem, TranslatedGlobalEvent};
+pub use news::{NewsType, NewsTag, NewsCategory, classify_news, NewsFeedQuery, NewsCursor};
+pub use campaign::{CampaignType, CampaignView};
\ No newline at end of file
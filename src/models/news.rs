@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::models::api::NewsItem;
+use crate::models::Language;
+
+/// The `type` of a news item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NewsType {
+    /// Type 0, used by every news item seen so far
+    Dispatch,
+    Unknown(i64),
+}
+
+impl From<i64> for NewsType {
+    fn from(value: i64) -> Self {
+        match value {
+            0 => NewsType::Dispatch,
+            other => NewsType::Unknown(other),
+        }
+    }
+}
+
+impl NewsType {
+    /// The value used by the API
+    pub fn value(&self) -> i64 {
+        match self {
+            NewsType::Dispatch => 0,
+            NewsType::Unknown(value) => *value,
+        }
+    }
+}
+
+impl Serialize for NewsType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.value())
+    }
+}
+
+/// A tag from the `tagIds` of a news item
+///
+/// The API has not been seen sending any tag IDs yet, so no tags are known and every tag is `Unknown`.
+/// Known tags will become variants once their IDs are documented.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NewsTag {
+    Unknown(String),
+}
+
+impl From<&str> for NewsTag {
+    fn from(value: &str) -> Self {
+        NewsTag::Unknown(value.to_string())
+    }
+}
+
+impl NewsTag {
+    /// The tag as sent by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            NewsTag::Unknown(value) => value,
+        }
+    }
+}
+
+impl Serialize for NewsTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// What a news item is about, for routing news to different channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewsCategory {
+    MajorOrder,
+    PlanetLiberated,
+    PlanetLost,
+    Defense,
+    /// Any other dispatch from Super Earth
+    Dispatch,
+    /// The message is in a language the classifier does not read
+    Unknown,
+}
+
+/// Phrases that mark the category of a message, checked in order against the whole words of the lowercased plain text
+const KEYWORDS: [(&str, NewsCategory); 12] = [
+    ("major order", NewsCategory::MajorOrder),
+    ("has been liberated", NewsCategory::PlanetLiberated),
+    ("have been liberated", NewsCategory::PlanetLiberated),
+    ("successfully liberated", NewsCategory::PlanetLiberated),
+    ("has fallen", NewsCategory::PlanetLost),
+    ("have fallen", NewsCategory::PlanetLost),
+    ("has been lost", NewsCategory::PlanetLost),
+    ("under attack", NewsCategory::Defense),
+    ("defend", NewsCategory::Defense),
+    ("defended", NewsCategory::Defense),
+    ("defending", NewsCategory::Defense),
+    ("defense campaign", NewsCategory::Defense),
+];
+
+/// Classify a news item by the content of its message
+///
+/// Every news item so far has the same `type` and no tags, so only the message tells them apart. The keywords
+/// are English, so news fetched in any other language is `NewsCategory::Unknown`; fetch the feed in English to
+/// classify it.
+///
+/// Arguments:
+///   item: &NewsItem - The news item to classify
+///   language: Language - The language the news item was fetched in
+pub fn classify_news(item: &NewsItem, language: Language) -> NewsCategory {
+    if language != Language::English {
+        return NewsCategory::Unknown;
+    }
+    // the words of the message between single spaces, so keywords only match whole words
+    let words: Vec<String> = item.markup().to_plain_text().to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect();
+    let message = format!(" {} ", words.join(" "));
+    KEYWORDS.iter()
+        .find(|(keyword, _)| message.contains(&format!(" {} ", keyword)))
+        .map_or(NewsCategory::Dispatch, |(_, category)| *category)
+}
+
+/// Query parameters for the news feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NewsFeedQuery {
+    /// Only return items published at or after this war time
+    pub from_timestamp: Option<i64>,
+    /// The maximum number of items to return
+    pub max_entries: Option<u32>,
+}
+
+impl NewsFeedQuery {
+    pub(crate) fn to_params(self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(from_timestamp) = self.from_timestamp {
+            params.push(("fromTimestamp", from_timestamp.to_string()));
+        }
+        if let Some(max_entries) = self.max_entries {
+            params.push(("maxEntries", max_entries.to_string()));
+        }
+        params
+    }
+}
+
+/// The newest news item seen so far, for fetching only newer items with `news_since`
+///
+/// Serializable so it can be stored between runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct NewsCursor {
+    pub published: i64,
+    pub id: i64,
+}
+
+impl NewsCursor {
+    /// A cursor positioned at a news item
+    pub fn at(item: &NewsItem) -> NewsCursor {
+        NewsCursor { published: item.published, id: item.id }
+    }
+
+    /// Keep the items newer than the cursor, oldest first and without duplicates, and move the cursor to the newest
+    ///
+    /// Arguments:
+    ///   items: Vec<NewsItem> - The items of a news feed response
+    pub fn advance(&mut self, mut items: Vec<NewsItem>) -> Vec<NewsItem> {
+        items.retain(|item| NewsCursor::at(item) > *self);
+        items.sort_by_key(NewsCursor::at);
+        items.dedup_by_key(|item| item.id);
+        if let Some(newest) = items.last() {
+            *self = NewsCursor::at(newest);
+        }
+        items
+    }
+}
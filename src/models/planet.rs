@@ -1,5 +1,45 @@
+use std::collections::HashMap;
+use serde::Serialize;
+
+use crate::models::Language;
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Planet{
     pub id: i64,
     pub name: String,
+    /// The ID of the sector the planet belongs to
+    pub sector: Option<i64>,
+    pub biome: Option<Biome>,
+    /// The environmental hazards on the planet
+    pub hazards: Vec<Hazard>,
+    pub description: Option<String>,
+    /// The name in other languages, by locale code such as `de-DE`
+    ///
+    /// The game keeps most planet names untranslated, so only names that differ from the English name are listed.
+    pub names: HashMap<String, String>,
 }
 
+impl Planet {
+    /// Get the name in a language, falling back to the English name
+    pub fn localized_name(&self, language: Language) -> &str {
+        self.names.get(language.to_str()).unwrap_or(&self.name)
+    }
+}
+
+/// The terrain and climate of a planet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Biome {
+    /// The key of the biome in `res/biomes.toml`
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+}
+
+/// An environmental hazard, such as meteor showers or acid storms
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hazard {
+    /// The key of the hazard in `res/hazards.toml`
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+}
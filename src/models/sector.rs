@@ -1,8 +1,55 @@
-use serde::Deserialize;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+use crate::models::Language;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sector {
     pub id: i64,
     pub name: String,
     pub planets: Vec<i64>,
+    /// The name in other languages, by locale code such as `de-DE`
+    ///
+    /// The game keeps most sector names untranslated, so only names that differ from the English name are listed.
+    #[serde(default)]
+    pub names: HashMap<String, String>,
+}
+
+impl Sector {
+    /// Get the name in a language, falling back to the English name
+    pub fn localized_name(&self, language: Language) -> &str {
+        self.names.get(language.to_str()).unwrap_or(&self.name)
+    }
+}
+
+/// The state of a sector, combining its planets from a WarInfo with their Status
+#[derive(Debug, Serialize)]
+pub struct SectorSummary {
+    pub id: i64,
+    pub name: String,
+    pub planets: Vec<i64>,
+    /// The number of planets owned by each faction
+    pub owners: HashMap<i64, i64>,
+    /// Planets with an active campaign or a health below their maximum
+    pub contested_planets: Vec<i64>,
+    pub players: i64,
+    /// Whether every planet in the sector is owned by humans
+    pub liberated: bool,
+}
+
+/// Problems found in the bundled sector table
+#[derive(Debug, Default, Serialize)]
+pub struct SectorConsistency {
+    /// Planets listed in more than one sector, with the IDs of those sectors
+    pub multiple_sectors: HashMap<i64, Vec<i64>>,
+    /// Planets not listed in any sector
+    pub no_sector: Vec<i64>,
+    /// Sector IDs shared by several sector names
+    pub duplicate_sector_ids: HashMap<i64, Vec<String>>,
+}
+
+impl SectorConsistency {
+    pub fn is_consistent(&self) -> bool {
+        self.multiple_sectors.is_empty() && self.no_sector.is_empty() && self.duplicate_sector_ids.is_empty()
+    }
 }
\ No newline at end of file
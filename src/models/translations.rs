@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::models::Language;
+
+/// The news and global events of a war in several languages
+#[derive(Debug, Default, Serialize)]
+pub struct Translations {
+    /// News items by `NewsItem::id`
+    pub news: HashMap<i64, TranslatedNewsItem>,
+    /// Global events by `GlobalEvent::event_id`
+    pub global_events: HashMap<i64, TranslatedGlobalEvent>,
+    /// The languages that failed to fetch, with their error, whose text is missing
+    pub errors: HashMap<Language, String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TranslatedNewsItem {
+    pub id: i64,
+    pub published: i64,
+    pub messages: HashMap<Language, String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TranslatedGlobalEvent {
+    pub event_id: i64,
+    pub titles: HashMap<Language, String>,
+    pub messages: HashMap<Language, String>,
+}
@@ -0,0 +1,202 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::models::api::WarInfo;
+use crate::models::{Language, Planet, Sector};
+use crate::{load_res, PLANETS};
+
+const PLANETS_HEADER: &str = "# Planets are either a name, or a table with a name and optional biome, hazards, description and translations by locale code.
+# Biomes and hazards refer to the keys in biomes.toml and hazards.toml.
+";
+
+/// A planet whose sector in a WarInfo differs from the bundled sector table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectorReassignment {
+    pub planet: i64,
+    /// The bundled sector ID, if the planet was in a sector
+    pub from: Option<i64>,
+    pub to: i64,
+}
+
+/// A bundled sector whose planets are in a different sector ID in a WarInfo
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectorRenumbering {
+    pub name: String,
+    pub from: i64,
+    pub to: i64,
+}
+
+/// The changes needed to bring the resource tables in line with a WarInfo
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceUpdate {
+    /// Planets in the WarInfo without a name
+    pub unnamed_planets: Vec<i64>,
+    /// Planets that moved to another sector
+    pub sector_reassignments: Vec<SectorReassignment>,
+    /// Sectors whose ID does not match the sector ID of their planets
+    pub renumbered_sectors: Vec<SectorRenumbering>,
+    /// Sector IDs in the WarInfo without a name
+    pub new_sectors: Vec<i64>,
+    /// The updated contents of `res/planets.toml`
+    pub planets_toml: String,
+    /// The updated contents of `res/sectors.toml`
+    pub sectors_toml: String,
+}
+
+impl ResourceUpdate {
+    /// Whether the resource tables already match the WarInfo
+    pub fn is_empty(&self) -> bool {
+        self.unnamed_planets.is_empty()
+            && self.sector_reassignments.is_empty()
+            && self.renumbered_sectors.is_empty()
+            && self.new_sectors.is_empty()
+    }
+}
+
+/// Compare the resource tables with a WarInfo and produce updated `planets.toml` and `sectors.toml` files
+///
+/// Planets and sectors are taken from the bundled tables, so the result does not depend on names added at runtime.
+/// Each sector name keeps the ID that most of its planets have in the WarInfo, and sector IDs without a name get a
+/// placeholder name. Planets without a name or with an empty name, and unnamed sectors, are marked with a `ToDo`
+/// comment for review.
+///
+/// Arguments:
+///   war_info: &WarInfo - The WarInfo to reconcile against, either live or loaded from a file
+pub fn reconcile_resources(war_info: &WarInfo) -> ResourceUpdate {
+    reconcile_tables(war_info, &PLANETS, load_res::parse_sectors())
+}
+
+/// Reconcile a planet and sector table with a WarInfo
+pub(crate) fn reconcile_tables(war_info: &WarInfo, bundled_planets: &HashMap<i64, Planet>, mut bundled: Vec<Sector>) -> ResourceUpdate {
+    let war_sectors: BTreeMap<i64, i64> = war_info.planet_infos.iter().map(|pi| (pi.index, pi.sector)).collect();
+    let bundled_sectors: HashMap<i64, i64> = bundled.iter()
+        .flat_map(|sector| sector.planets.iter().map(|planet| (*planet, sector.id)))
+        .collect();
+
+    let sector_reassignments: Vec<SectorReassignment> = war_sectors.iter()
+        .filter(|(planet, sector)| bundled_sectors.get(planet) != Some(sector))
+        .map(|(planet, sector)| SectorReassignment { planet: *planet, from: bundled_sectors.get(planet).copied(), to: *sector })
+        .collect();
+
+    // a sector name belongs to the ID most of its planets are in, which also resolves sectors sharing an ID
+    let mut names: BTreeMap<i64, String> = BTreeMap::new();
+    let mut renumbered_sectors = Vec::new();
+    bundled.sort_by(|a, b| a.id.cmp(&b.id).then(a.name.cmp(&b.name)));
+    for sector in &bundled {
+        let mut counts: HashMap<i64, usize> = HashMap::new();
+        for planet in &sector.planets {
+            if let Some(war_sector) = war_sectors.get(planet) {
+                *counts.entry(*war_sector).or_insert(0) += 1;
+            }
+        }
+        let id = counts.into_iter()
+            .max_by_key(|(id, count)| (*count, std::cmp::Reverse(*id)))
+            .map(|(id, _)| id)
+            .unwrap_or(sector.id);
+        if names.contains_key(&id) {
+            // another name already claimed the ID, both names describe the same planets
+            continue;
+        }
+        if id != sector.id {
+            renumbered_sectors.push(SectorRenumbering { name: sector.name.clone(), from: sector.id, to: id });
+        }
+        names.insert(id, sector.name.clone());
+    }
+
+    let mut sector_planets: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for (planet, sector) in &war_sectors {
+        sector_planets.entry(*sector).or_default().push(*planet);
+    }
+    // keep bundled sectors the WarInfo does not mention, so an incomplete WarInfo does not drop them
+    for sector in &bundled {
+        if let Some(id) = names.iter().find(|(_, name)| **name == sector.name).map(|(id, _)| *id) {
+            let planets = sector_planets.entry(id).or_default();
+            planets.extend(sector.planets.iter().filter(|p| !war_sectors.contains_key(p)));
+            planets.sort();
+        }
+    }
+    let new_sectors: Vec<i64> = sector_planets.keys().filter(|id| !names.contains_key(id)).copied().collect();
+
+    let mut planets: BTreeMap<i64, Option<&Planet>> = bundled_planets.values().map(|p| (p.id, Some(p))).collect();
+    for planet in war_sectors.keys() {
+        planets.entry(*planet).or_insert(None);
+    }
+    let unnamed = |planet: &Option<&Planet>| planet.is_none_or(|planet| planet.name.trim().is_empty());
+    let unnamed_planets: Vec<i64> = planets.iter().filter(|(_, planet)| unnamed(planet)).map(|(id, _)| *id).collect();
+
+    // writing to a String never fails, so the results of writeln! are ignored
+    let mut planets_toml = String::from(PLANETS_HEADER);
+    for (id, planet) in &planets {
+        let entry = planet.map_or_else(|| quote(""), planet_entry);
+        if unnamed(planet) {
+            let _ = writeln!(planets_toml, "{} = {} # ToDo: name this planet", id, entry);
+        } else {
+            let _ = writeln!(planets_toml, "{} = {}", id, entry);
+        }
+    }
+
+    let sector_translations: HashMap<i64, &HashMap<String, String>> = bundled.iter()
+        .filter_map(|sector| names.iter().find(|(_, name)| **name == sector.name).map(|(id, _)| (*id, &sector.names)))
+        .collect();
+    let mut sectors_toml = String::new();
+    for (id, planets) in &sector_planets {
+        if !sectors_toml.is_empty() {
+            sectors_toml.push('\n');
+        }
+        match names.get(id) {
+            Some(name) => { let _ = writeln!(sectors_toml, "[{}]", key(name)); }
+            None => { let _ = writeln!(sectors_toml, "[\"Sector {}\"] # ToDo: name this sector", id); }
+        }
+        let planets: Vec<String> = planets.iter().map(|p| p.to_string()).collect();
+        let _ = writeln!(sectors_toml, "id = {}\nplanets = [{}]", id, planets.join(", "));
+        if let Some(names) = sector_translations.get(id).filter(|names| !names.is_empty()) {
+            let _ = writeln!(sectors_toml, "names = {}", names_table(names));
+        }
+    }
+
+    ResourceUpdate { unnamed_planets, sector_reassignments, renumbered_sectors, new_sectors, planets_toml, sectors_toml }
+}
+
+/// Write a planet as a name, or as an inline table if it has details
+fn planet_entry(planet: &Planet) -> String {
+    if planet.biome.is_none() && planet.hazards.is_empty() && planet.description.is_none() && planet.names.is_empty() {
+        return quote(&planet.name);
+    }
+    let mut fields = vec![format!("name = {}", quote(&planet.name))];
+    if let Some(biome) = &planet.biome {
+        fields.push(format!("biome = {}", quote(&biome.slug)));
+    }
+    if !planet.hazards.is_empty() {
+        let hazards: Vec<String> = planet.hazards.iter().map(|h| quote(&h.slug)).collect();
+        fields.push(format!("hazards = [{}]", hazards.join(", ")));
+    }
+    if let Some(description) = &planet.description {
+        fields.push(format!("description = {}", quote(description)));
+    }
+    if !planet.names.is_empty() {
+        fields.push(format!("names = {}", names_table(&planet.names)));
+    }
+    format!("{{ {} }}", fields.join(", "))
+}
+
+/// Write translations as an inline table, in the order of `Language::all`
+fn names_table(names: &HashMap<String, String>) -> String {
+    let mut entries: Vec<(&String, &String)> = names.iter().collect();
+    entries.sort_by_key(|(language, _)| Language::all().position(|l| l.to_str() == language.as_str()).unwrap_or(usize::MAX));
+    let entries: Vec<String> = entries.into_iter().map(|(language, name)| format!("{} = {}", key(language), quote(name))).collect();
+    format!("{{ {} }}", entries.join(", "))
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn key(name: &str) -> String {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
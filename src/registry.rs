@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{RwLock, RwLockReadGuard};
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::error::HelldiversError;
+use crate::models::{Faction, Language, Planet, Sector};
+use crate::load_res::{self, FactionEntry, PlanetEntry};
+use crate::{FACTIONS, PLANETS, SECTORS};
+
+/// The registry used to resolve planet, faction and sector names
+///
+/// Starts out as the bundled tables, and can be extended at runtime with `merge_registry` or `load_registry_file`.
+pub static REGISTRY: Lazy<RwLock<Registry>> = Lazy::new(|| RwLock::new(Registry::bundled()));
+
+/// A set of planet, faction and sector tables
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    pub planets: HashMap<i64, Planet>,
+    pub factions: HashMap<i64, Faction>,
+    pub sectors: HashMap<i64, Sector>,
+    planet_sectors: HashMap<i64, i64>,
+    /// Every sector name with its ID, including names whose ID was taken by another sector
+    sector_names: Vec<(i64, String)>,
+}
+
+/// The file format of a registry, shared by TOML and JSON
+///
+/// Planets map an ID to a name or a table of details, factions map an ID to a name and sectors map a name to
+/// their ID and planets, matching the files in `res/`.
+#[derive(Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    planets: HashMap<String, PlanetEntry>,
+    #[serde(default)]
+    factions: HashMap<String, FactionEntry>,
+    #[serde(default)]
+    sectors: HashMap<String, SectorEntry>,
+}
+
+#[derive(Deserialize)]
+struct SectorEntry {
+    id: i64,
+    planets: Vec<i64>,
+    #[serde(default)]
+    names: HashMap<String, String>,
+}
+
+impl Registry {
+    /// The tables compiled into the crate
+    pub fn bundled() -> Registry {
+        Registry {
+            planets: PLANETS.clone(),
+            factions: FACTIONS.clone(),
+            sectors: SECTORS.clone(),
+            planet_sectors: load_res::load_planet_sectors(),
+            sector_names: load_res::parse_sectors().into_iter().map(|sector| (sector.id, sector.name)).collect(),
+        }
+    }
+
+    /// Parse a registry from TOML
+    ///
+    /// Arguments:
+    ///   raw: &str - The TOML document, with optional `[planets]`, `[factions]` and `[sectors.<name>]` tables
+    pub fn from_toml_str(raw: &str) -> Result<Registry, HelldiversError> {
+        let file: RegistryFile = toml::from_str(raw).map_err(|e| HelldiversError::ResourceError(e.to_string()))?;
+        Registry::from_file(file)
+    }
+
+    /// Parse a registry from JSON
+    ///
+    /// Arguments:
+    ///   raw: &str - The JSON document, with optional `planets`, `factions` and `sectors` objects
+    pub fn from_json_str(raw: &str) -> Result<Registry, HelldiversError> {
+        let file: RegistryFile = serde_json::from_str(raw)?;
+        Registry::from_file(file)
+    }
+
+    /// Read a registry from a TOML or JSON file, chosen by the file extension
+    ///
+    /// Arguments:
+    ///   path: impl AsRef<Path> - The path of the file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Registry, HelldiversError> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| HelldiversError::ResourceError(format!("{}: {}", path.display(), e)))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Registry::from_json_str(&raw),
+            _ => Registry::from_toml_str(&raw),
+        }
+    }
+
+    fn from_file(file: RegistryFile) -> Result<Registry, HelldiversError> {
+        let mut registry = Registry::default();
+        for (id, entry) in file.planets {
+            let id = parse_id(&id)?;
+            registry.planets.insert(id, entry.into_planet(id).map_err(HelldiversError::ResourceError)?);
+        }
+        for (id, entry) in file.factions {
+            let id = parse_id(&id)?;
+            registry.factions.insert(id, entry.into_faction(id));
+        }
+        for (name, entry) in file.sectors {
+            for planet in &entry.planets {
+                registry.planet_sectors.insert(*planet, entry.id);
+            }
+            registry.add_sector_name(entry.id, &name);
+            registry.sectors.insert(entry.id, Sector { id: entry.id, name, planets: entry.planets, names: entry.names });
+        }
+        registry.sync_planet_sectors();
+        Ok(registry)
+    }
+
+    /// Add the entries of another registry, replacing any entries with the same ID
+    ///
+    /// A sector replaces the sector with the same ID, and takes its planets from whichever sector held them before.
+    /// Planets of the replaced sector that the new sector does not list are left without a sector.
+    pub fn merge(&mut self, other: Registry) {
+        self.planets.extend(other.planets);
+        self.factions.extend(other.factions);
+        for sector in other.sectors.values() {
+            if let Some(replaced) = self.sectors.get(&sector.id) {
+                for planet in &replaced.planets {
+                    if self.planet_sectors.get(planet) == Some(&sector.id) {
+                        self.planet_sectors.remove(planet);
+                    }
+                }
+            }
+            for planet in &sector.planets {
+                if let Some(previous) = self.planet_sectors.get(planet).and_then(|id| self.sectors.get_mut(id)) {
+                    if previous.id != sector.id {
+                        previous.planets.retain(|p| p != planet);
+                    }
+                }
+            }
+        }
+        self.planet_sectors.extend(other.planet_sectors);
+        self.sectors.extend(other.sectors);
+        for (id, name) in &other.sector_names {
+            self.add_sector_name(*id, name);
+        }
+        self.sync_planet_sectors();
+    }
+
+    /// Every sector name with the ID it resolves to, for searching by name
+    pub fn sector_names(&self) -> &[(i64, String)] {
+        &self.sector_names
+    }
+
+    /// Record a sector name, moving it to `id` if it was known under another ID
+    fn add_sector_name(&mut self, id: i64, name: &str) {
+        self.sector_names.retain(|(_, known)| known != name);
+        self.sector_names.push((id, name.to_string()));
+    }
+
+    /// Set the sector of each planet from the sector tables
+    fn sync_planet_sectors(&mut self) {
+        for planet in self.planets.values_mut() {
+            planet.sector = self.planet_sectors.get(&planet.id).copied();
+        }
+    }
+
+    pub fn planet(&self, id: i64) -> Option<Planet> {
+        self.planets.get(&id).cloned()
+    }
+
+    pub fn planet_name(&self, id: i64) -> Option<String> {
+        self.planets.get(&id).map(|planet| planet.name.clone())
+    }
+
+    pub fn faction_name(&self, id: i64) -> Option<String> {
+        self.factions.get(&id).map(|faction| faction.name.clone())
+    }
+
+    pub fn sector_name(&self, id: i64) -> Option<String> {
+        self.sectors.get(&id).map(|sector| sector.name.clone())
+    }
+
+    pub fn localized_planet_name(&self, id: i64, language: Language) -> Option<String> {
+        self.planets.get(&id).map(|planet| planet.localized_name(language).to_string())
+    }
+
+    pub fn localized_faction_name(&self, id: i64, language: Language) -> Option<String> {
+        self.factions.get(&id).map(|faction| faction.localized_name(language).to_string())
+    }
+
+    pub fn localized_sector_name(&self, id: i64, language: Language) -> Option<String> {
+        self.sectors.get(&id).map(|sector| sector.localized_name(language).to_string())
+    }
+
+    pub fn planet_sector(&self, id: i64) -> Option<i64> {
+        self.planet_sectors.get(&id).copied()
+    }
+}
+
+fn parse_id(id: &str) -> Result<i64, HelldiversError> {
+    id.trim().parse().map_err(|_| HelldiversError::ResourceError(format!("Invalid ID: {}", id)))
+}
+
+/// Get read access to the current registry
+pub fn registry() -> RwLockReadGuard<'static, Registry> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Add entries to the current registry, replacing any entries with the same ID
+///
+/// Arguments:
+///   other: Registry - The entries to add
+pub fn merge_registry(other: Registry) {
+    REGISTRY.write().unwrap_or_else(|e| e.into_inner()).merge(other);
+}
+
+/// Replace the current registry
+///
+/// Arguments:
+///   registry: Registry - The new registry, use `Registry::bundled()` to restore the compiled-in tables
+pub fn set_registry(registry: Registry) {
+    *REGISTRY.write().unwrap_or_else(|e| e.into_inner()) = registry;
+}
+
+/// Read a TOML or JSON file and add its entries to the current registry
+///
+/// Arguments:
+///   path: impl AsRef<Path> - The path of the file
+pub fn load_registry_file(path: impl AsRef<Path>) -> Result<(), HelldiversError> {
+    merge_registry(Registry::from_path(path)?);
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::models::api::{GlobalEvent, PlanetInfo, Position};
+
+mod svg;
+#[cfg(feature = "raster")]
+mod raster;
+
+pub use svg::render_svg;
+#[cfg(feature = "raster")]
+pub use raster::render_png;
+
+/// An RGB colour used when drawing the galaxy map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Colour {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Colour {
+        Colour { r, g, b }
+    }
+
+    /// Get the colour as a CSS hex string, e.g. `#ffe711`
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// The style of a rendered galaxy map
+#[derive(Debug, Clone)]
+pub struct MapStyle {
+    pub width: u32,
+    pub height: u32,
+    pub background: Colour,
+    /// Planet colours by owning faction ID
+    pub faction_colours: HashMap<i64, Colour>,
+    /// The colour of planets whose owner has no entry in `faction_colours`
+    pub unknown_faction_colour: Colour,
+    pub supply_line_colour: Colour,
+    pub attack_colour: Colour,
+    pub campaign_colour: Colour,
+    pub label_colour: Colour,
+    /// The planet radius in pixels
+    pub planet_radius: f32,
+    pub show_supply_lines: bool,
+    pub show_attacks: bool,
+    pub show_campaigns: bool,
+    pub show_sector_labels: bool,
+}
+
+impl Default for MapStyle {
+    fn default() -> Self {
+        MapStyle {
+            width: 1024,
+            height: 1024,
+            background: Colour::rgb(0x0b, 0x0e, 0x14),
+            faction_colours: HashMap::from([
+                (1, Colour::rgb(0x2c, 0xb8, 0xe8)),
+                (2, Colour::rgb(0xff, 0xb8, 0x00)),
+                (3, Colour::rgb(0xfe, 0x6d, 0x6a)),
+                (4, Colour::rgb(0xce, 0x64, 0xf8)),
+            ]),
+            unknown_faction_colour: Colour::rgb(0x80, 0x80, 0x80),
+            supply_line_colour: Colour::rgb(0x3a, 0x44, 0x55),
+            attack_colour: Colour::rgb(0xff, 0x30, 0x30),
+            campaign_colour: Colour::rgb(0xff, 0xe7, 0x11),
+            label_colour: Colour::rgb(0x9a, 0xa5, 0xb8),
+            planet_radius: 5.0,
+            show_supply_lines: true,
+            show_attacks: true,
+            show_campaigns: true,
+            show_sector_labels: true,
+        }
+    }
+}
+
+impl MapStyle {
+    /// Get the colour of a planet owned by a faction
+    pub fn faction_colour(&self, faction: i64) -> Colour {
+        self.faction_colours.get(&faction).copied().unwrap_or(self.unknown_faction_colour)
+    }
+
+    /// Project a galaxy position, roughly within -1.0..=1.0 on both axes with y pointing up, onto the image
+    pub(crate) fn project(&self, position: &Position) -> (f32, f32) {
+        let margin = self.planet_radius * 3.0;
+        let x = margin + (position.x as f32 + 1.0) / 2.0 * (self.width as f32 - 2.0 * margin);
+        let y = margin + (1.0 - position.y as f32) / 2.0 * (self.height as f32 - 2.0 * margin);
+        (x, y)
+    }
+}
+
+/// Planets to draw attention to on a rendered map
+#[derive(Debug, Clone)]
+pub enum Highlight {
+    /// Every planet in a sector
+    Sector(i64),
+    /// A set of planets by index
+    Planets(Vec<i64>),
+}
+
+impl Highlight {
+    /// Whether a planet is part of the highlight
+    pub fn contains(&self, planet: &PlanetInfo) -> bool {
+        match self {
+            Highlight::Sector(sector) => planet.sector == *sector,
+            Highlight::Planets(indices) => indices.contains(&planet.index),
+        }
+    }
+}
+
+impl From<&GlobalEvent> for Highlight {
+    fn from(event: &GlobalEvent) -> Self {
+        Highlight::Planets(event.planet_indices.clone())
+    }
+}
+
+/// Get the centre of each sector on the image, from the positions of its planets
+pub(crate) fn sector_centres(war_info: &crate::WarInfo, style: &MapStyle) -> Vec<(i64, (f32, f32))> {
+    let mut sums: HashMap<i64, (f32, f32, f32)> = HashMap::new();
+    for pi in &war_info.planet_infos {
+        let (x, y) = style.project(&pi.position);
+        let sum = sums.entry(pi.sector).or_insert((0.0, 0.0, 0.0));
+        *sum = (sum.0 + x, sum.1 + y, sum.2 + 1.0);
+    }
+    let mut centres: Vec<(i64, (f32, f32))> = sums.into_iter()
+        .map(|(sector, (x, y, n))| (sector, (x / n, y / n)))
+        .collect();
+    centres.sort_by_key(|(sector, _)| *sector);
+    centres
+}
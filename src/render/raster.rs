@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+use crate::error::HelldiversError;
+use crate::models::api::{PlanetInfo, Status, WarInfo};
+use crate::render::{Colour, Highlight, MapStyle};
+
+/// The opacity of planets outside of the highlight
+const DIMMED_ALPHA: u8 = 70;
+
+/// Render the galaxy map as a PNG image
+///
+/// The image is `style.width` by `style.height` pixels. Sector labels are not drawn, as the raster renderer
+/// does not depend on any fonts.
+///
+/// Arguments:
+///     war_info: &WarInfo - The WarInfo to get the planet positions and supply lines from
+///     status: &Status - The Status to get the planet owners, attacks and campaigns from
+///     style: &MapStyle - The style to render the map with
+///     highlight: Option<&Highlight> - The planets to highlight, dimming every other planet
+pub fn render_png(war_info: &WarInfo, status: &Status, style: &MapStyle, highlight: Option<&Highlight>) -> Result<Vec<u8>, HelldiversError> {
+    let mut pixmap = Pixmap::new(style.width, style.height)
+        .ok_or_else(|| HelldiversError::RenderError(format!("Invalid image size: {}x{}", style.width, style.height)))?;
+    pixmap.fill(Color::from_rgba8(style.background.r, style.background.g, style.background.b, 255));
+
+    let infos: HashMap<i64, &PlanetInfo> = war_info.planet_infos.iter().map(|pi| (pi.index, pi)).collect();
+    let owners: HashMap<i64, i64> = status.planet_status.iter().map(|ps| (ps.index, ps.owner)).collect();
+    let r = style.planet_radius;
+
+    if style.show_supply_lines {
+        let mut path = PathBuilder::new();
+        for pi in &war_info.planet_infos {
+            let (x1, y1) = style.project(&pi.position);
+            for waypoint in pi.waypoints.iter().filter_map(|w| infos.get(w)) {
+                let (x2, y2) = style.project(&waypoint.position);
+                path.move_to(x1, y1);
+                path.line_to(x2, y2);
+            }
+        }
+        if let Some(path) = path.finish() {
+            pixmap.stroke_path(&path, &paint(style.supply_line_colour, 255), &Stroke { width: 1.0, ..Default::default() }, Transform::identity(), None);
+        }
+    }
+
+    for pi in war_info.planet_infos.iter().filter(|pi| !pi.disabled) {
+        let (x, y) = style.project(&pi.position);
+        let colour = style.faction_colour(owners.get(&pi.index).copied().unwrap_or(pi.initial_owner));
+        let highlighted = highlight.map(|h| h.contains(pi));
+        let alpha = if highlighted == Some(false) { DIMMED_ALPHA } else { 255 };
+        if let Some(circle) = PathBuilder::from_circle(x, y, r) {
+            pixmap.fill_path(&circle, &paint(colour, alpha), FillRule::Winding, Transform::identity(), None);
+        }
+        if highlighted == Some(true) {
+            if let Some(ring) = PathBuilder::from_circle(x, y, r * 1.6) {
+                pixmap.stroke_path(&ring, &paint(Colour::rgb(255, 255, 255), 255), &Stroke { width: 1.5, ..Default::default() }, Transform::identity(), None);
+            }
+        }
+    }
+
+    if style.show_campaigns {
+        for pi in status.campaigns.iter().filter_map(|c| infos.get(&c.planet_index)) {
+            let (x, y) = style.project(&pi.position);
+            if let Some(ring) = PathBuilder::from_circle(x, y, r * 2.0) {
+                pixmap.stroke_path(&ring, &paint(style.campaign_colour, 255), &Stroke { width: 2.0, ..Default::default() }, Transform::identity(), None);
+            }
+        }
+    }
+
+    if style.show_attacks {
+        let attack_paint = paint(style.attack_colour, 255);
+        for attack in &status.planet_attacks {
+            let (Some(source), Some(target)) = (infos.get(&attack.source), infos.get(&attack.target)) else { continue };
+            let (x1, y1) = style.project(&source.position);
+            let (x2, y2) = style.project(&target.position);
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(f32::EPSILON);
+            let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+            // stop the arrow at the edge of the target planet rather than its centre
+            let (tip_x, tip_y) = (x2 - dx * r * 1.5, y2 - dy * r * 1.5);
+            let head = r * 1.6;
+
+            let mut line = PathBuilder::new();
+            line.move_to(x1, y1);
+            line.line_to(tip_x - dx * head, tip_y - dy * head);
+            if let Some(line) = line.finish() {
+                pixmap.stroke_path(&line, &attack_paint, &Stroke { width: 2.0, ..Default::default() }, Transform::identity(), None);
+            }
+
+            let mut arrow = PathBuilder::new();
+            arrow.move_to(tip_x, tip_y);
+            arrow.line_to(tip_x - dx * head - dy * head * 0.5, tip_y - dy * head + dx * head * 0.5);
+            arrow.line_to(tip_x - dx * head + dy * head * 0.5, tip_y - dy * head - dx * head * 0.5);
+            arrow.close();
+            if let Some(arrow) = arrow.finish() {
+                pixmap.fill_path(&arrow, &attack_paint, FillRule::Winding, Transform::identity(), None);
+            }
+        }
+    }
+
+    pixmap.encode_png().map_err(|e| HelldiversError::RenderError(e.to_string()))
+}
+
+fn paint(colour: Colour, alpha: u8) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(colour.r, colour.g, colour.b, alpha);
+    paint.anti_alias = true;
+    paint
+}
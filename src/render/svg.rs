@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::models::api::{PlanetInfo, Status, WarInfo};
+use crate::render::{sector_centres, MapStyle};
+use crate::get_sector_name;
+
+/// Render the galaxy map as an SVG document
+///
+/// Arguments:
+///     war_info: &WarInfo - The WarInfo to get the planet positions, supply lines and sectors from
+///     status: &Status - The Status to get the planet owners, attacks and campaigns from
+///     style: &MapStyle - The style to render the map with
+pub fn render_svg(war_info: &WarInfo, status: &Status, style: &MapStyle) -> String {
+    let infos: HashMap<i64, &PlanetInfo> = war_info.planet_infos.iter().map(|pi| (pi.index, pi)).collect();
+    let owners: HashMap<i64, i64> = status.planet_status.iter().map(|ps| (ps.index, ps.owner)).collect();
+    let r = style.planet_radius;
+
+    // writing to a String never fails, so the results of write! are ignored throughout
+    let mut svg = String::new();
+    let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#, w = style.width, h = style.height);
+    let _ = writeln!(svg, r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker></defs>"#, style.attack_colour.to_hex());
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="{}"/>"#, style.background.to_hex());
+
+    if style.show_supply_lines {
+        let _ = writeln!(svg, r#"<g class="supply-lines" stroke="{}" stroke-width="1">"#, style.supply_line_colour.to_hex());
+        for pi in &war_info.planet_infos {
+            let (x1, y1) = style.project(&pi.position);
+            for waypoint in pi.waypoints.iter().filter_map(|w| infos.get(w)) {
+                let (x2, y2) = style.project(&waypoint.position);
+                let _ = writeln!(svg, r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}"/>"#);
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if style.show_sector_labels {
+        let _ = writeln!(svg, r#"<g class="sector-labels" fill="{}" font-family="sans-serif" font-size="{:.0}" text-anchor="middle" opacity="0.6">"#, style.label_colour.to_hex(), r * 2.0);
+        for (sector, (x, y)) in sector_centres(war_info, style) {
+            let name = get_sector_name(sector).unwrap_or_default();
+            if !name.is_empty() {
+                let _ = writeln!(svg, r#"<text x="{x:.1}" y="{y:.1}">{}</text>"#, escape(&name.to_uppercase()));
+            }
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    let _ = writeln!(svg, r#"<g class="planets">"#);
+    for pi in war_info.planet_infos.iter().filter(|pi| !pi.disabled) {
+        let (x, y) = style.project(&pi.position);
+        let colour = style.faction_colour(owners.get(&pi.index).copied().unwrap_or(pi.initial_owner));
+        let _ = writeln!(svg, r#"<circle cx="{x:.1}" cy="{y:.1}" r="{r:.1}" fill="{}"><title>{}</title></circle>"#, colour.to_hex(), escape(&pi.planet_name));
+    }
+    let _ = writeln!(svg, "</g>");
+
+    if style.show_campaigns {
+        let _ = writeln!(svg, r#"<g class="campaigns" fill="none" stroke="{}" stroke-width="2">"#, style.campaign_colour.to_hex());
+        for pi in status.campaigns.iter().filter_map(|c| infos.get(&c.planet_index)) {
+            let (x, y) = style.project(&pi.position);
+            let _ = writeln!(svg, r#"<circle cx="{x:.1}" cy="{y:.1}" r="{:.1}"/>"#, r * 2.0);
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    if style.show_attacks {
+        let _ = writeln!(svg, r#"<g class="attacks" stroke="{}" stroke-width="2" marker-end="url(#arrow)">"#, style.attack_colour.to_hex());
+        for attack in &status.planet_attacks {
+            let (Some(source), Some(target)) = (infos.get(&attack.source), infos.get(&attack.target)) else { continue };
+            let (x1, y1) = style.project(&source.position);
+            let (x2, y2) = style.project(&target.position);
+            // stop the arrow at the edge of the target planet rather than its centre
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(f32::EPSILON);
+            let (x2, y2) = (x2 - (x2 - x1) / length * r * 1.5, y2 - (y2 - y1) / length * r * 1.5);
+            let _ = writeln!(svg, r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}"/>"#);
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Escape text for use in SVG content
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -1,14 +1,84 @@
 use crate::BASE_URL;
-use crate::models::api::{Status, WarInfo, WarTime, NewsItem};
-use crate::models::Language;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use crate::models::api::{Status, WarInfo, WarTime, WarId, NewsItem};
+use crate::models::{Language, NewsCursor, NewsFeedQuery, Translations};
 use crate::error::HelldiversError;
 
 
+/// How long `current_war_id` trusts a discovered war ID before asking the API again
+pub const WAR_ID_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The war ID discovered by `current_war_id`
+static CURRENT_WAR_ID: Lazy<WarIdCache> = Lazy::new(WarIdCache::default);
+
+/// Get the ID of the active war season
+///
+/// The ID is cached for `WAR_ID_CACHE_TTL`. If the API cannot be reached once the cache has expired,
+/// the last known ID is returned rather than an error.
+pub async fn current_war_id() -> Result<i64, HelldiversError> {
+    if let Some(id) = CURRENT_WAR_ID.get(Instant::now()) {
+        return Ok(id);
+    }
+    match refresh_current_war_id().await {
+        Ok(id) => Ok(id),
+        Err(e) => CURRENT_WAR_ID.stale().ok_or(e),
+    }
+}
+
+/// Ask the API for the ID of the active war season, bypassing and then updating the cache
+///
+/// Call this after a request fails with `HelldiversError::InvalidWarId`, as the season may have changed.
+pub async fn refresh_current_war_id() -> Result<i64, HelldiversError> {
+    let url = format!("{}/WarSeason/current/WarID", BASE_URL);
+
+    let response = reqwest::get(url).await?;
+
+    if !&response.status().is_success() {
+        return Err(HelldiversError::from(response));
+    }
+
+    let war_id: WarId = response.json().await?;
+
+    CURRENT_WAR_ID.set(war_id.id, Instant::now());
+    Ok(war_id.id)
+}
+
+/// A war ID and when it was discovered
+#[derive(Default)]
+pub(crate) struct WarIdCache {
+    entry: Mutex<Option<(i64, Instant)>>,
+}
+
+impl WarIdCache {
+    fn entry(&self) -> std::sync::MutexGuard<'_, Option<(i64, Instant)>> {
+        self.entry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn set(&self, id: i64, now: Instant) {
+        *self.entry() = Some((id, now));
+    }
+
+    /// The cached ID, if it was discovered less than `WAR_ID_CACHE_TTL` before `now`
+    pub(crate) fn get(&self, now: Instant) -> Option<i64> {
+        self.entry().filter(|(_, at)| now.saturating_duration_since(*at) < WAR_ID_CACHE_TTL).map(|(id, _)| id)
+    }
+
+    /// The cached ID, however old
+    pub(crate) fn stale(&self) -> Option<i64> {
+        self.entry().map(|(id, _)| id)
+    }
+}
+
 /// Get the current status of a war
 ///
 /// Arguments:
 ///    war_id: i64 - The ID of the war to get the status of
-///  language: Language - The language to get the news feed in
+///  language: Language - The language to get the in-game text and planet names in
 pub async fn get_status(war_id: i64, language: Language) -> Result<Status, HelldiversError> {
     let url = format!("{}/WarSeason/{}/Status", BASE_URL, war_id);
 
@@ -27,17 +97,18 @@ pub async fn get_status(war_id: i64, language: Language) -> Result<Status, Helld
 
     let mut status: Status = response.json().await?;
 
+    // planet names follow the language of the in-game text
     for campaign in &mut status.campaigns {
-        campaign.planet_name = crate::get_planet_name(campaign.planet_index).unwrap_or_default();
+        campaign.planet_name = crate::get_localized_planet_name(campaign.planet_index, language).unwrap_or_default();
     }
 
     for planet_attack in &mut status.planet_attacks {
-        planet_attack.source_name = crate::get_planet_name(planet_attack.source).unwrap_or_default();
-        planet_attack.target_name = crate::get_planet_name(planet_attack.target).unwrap_or_default();
+        planet_attack.source_name = crate::get_localized_planet_name(planet_attack.source, language).unwrap_or_default();
+        planet_attack.target_name = crate::get_localized_planet_name(planet_attack.target, language).unwrap_or_default();
     }
 
     for planet_status in &mut status.planet_status {
-        planet_status.planet_name = crate::get_planet_name(planet_status.index).unwrap_or_default();
+        planet_status.planet_name = crate::get_localized_planet_name(planet_status.index, language).unwrap_or_default();
     }
 
     Ok(status)
@@ -83,12 +154,22 @@ pub async fn get_war_time(war_id: i64) -> Result<i64, HelldiversError> {
     Ok(war_time.time)
 }
 
-// Get the news feed for a war
+/// Get the news feed for a war
 ///
 /// Arguments:
 ///   war_id: i64 - The ID of the war to get the news feed for
 ///  language: Language - The language to get the news feed in
 pub async fn get_news_feed(war_id: i64, language: Language) -> Result<Vec<NewsItem>, HelldiversError> {
+    get_news_feed_with(war_id, language, NewsFeedQuery::default()).await
+}
+
+/// Get the news feed for a war, limited by query parameters
+///
+/// Arguments:
+///   war_id: i64 - The ID of the war to get the news feed for
+///   language: Language - The language to get the news feed in
+///   query: NewsFeedQuery - The earliest war time and maximum number of items to return
+pub async fn get_news_feed_with(war_id: i64, language: Language, query: NewsFeedQuery) -> Result<Vec<NewsItem>, HelldiversError> {
     let url = format!("{}/NewsFeed/{}", BASE_URL, war_id);
 
     let mut headers = reqwest::header::HeaderMap::new();
@@ -97,6 +178,7 @@ pub async fn get_news_feed(war_id: i64, language: Language) -> Result<Vec<NewsIt
     let response = reqwest::Client::new()
         .get(url)
         .headers(headers)
+        .query(&query.to_params())
         .send()
         .await?;
 
@@ -107,4 +189,103 @@ pub async fn get_news_feed(war_id: i64, language: Language) -> Result<Vec<NewsIt
     let news_feed: Vec<NewsItem> = response.json().await?;
 
     Ok(news_feed)
-}
\ No newline at end of file
+}
+
+/// The number of news items requested per page by `news_since`
+pub const NEWS_PAGE_SIZE: u32 = 64;
+
+/// Get the news items published after a cursor, and move the cursor to the newest item
+///
+/// The feed is fetched in pages of `NEWS_PAGE_SIZE` items, each starting at the newest item of the previous page,
+/// until a page comes back short or brings nothing new. Items are returned oldest first, and an item is never
+/// returned twice for the same cursor. Start with `NewsCursor::default()` to get the whole feed.
+///
+/// Arguments:
+///   war_id: i64 - The ID of the war to get the news feed for
+///   language: Language - The language to get the news feed in
+///   cursor: &mut NewsCursor - The newest item seen so far
+pub async fn news_since(war_id: i64, language: Language, cursor: &mut NewsCursor) -> Result<Vec<NewsItem>, HelldiversError> {
+    paginate_news(cursor, NEWS_PAGE_SIZE, |query| get_news_feed_with(war_id, language, query)).await
+}
+
+/// Fetch pages of news from a cursor until the feed is exhausted
+pub(crate) async fn paginate_news<F, Fut>(cursor: &mut NewsCursor, page_size: u32, mut fetch: F) -> Result<Vec<NewsItem>, HelldiversError>
+where
+    F: FnMut(NewsFeedQuery) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<NewsItem>, HelldiversError>>,
+{
+    let mut news = Vec::new();
+    loop {
+        // the timestamp is inclusive, so items published alongside the cursor are filtered by ID
+        let query = NewsFeedQuery { from_timestamp: Some(cursor.published), max_entries: Some(page_size) };
+        let page = fetch(query).await?;
+        let full = page.len() >= page_size as usize;
+        let new_items = cursor.advance(page);
+        if new_items.is_empty() {
+            break;
+        }
+        news.extend(new_items);
+        if !full {
+            break;
+        }
+    }
+    Ok(news)
+}
+
+/// Get the news feed and global events of a war in several languages at once
+///
+/// The languages are fetched concurrently, and the entries are matched up by their ID. A language whose Status or
+/// news feed fails is left out and its error is recorded in `Translations::errors`, so one failing language does
+/// not lose the others. An error is only returned if every language fails.
+///
+/// Arguments:
+///   war_id: i64 - The ID of the war to get the news feed and global events for
+///   languages: impl IntoIterator<Item = Language> - The languages to fetch, e.g. `Language::all()`
+pub async fn get_translations(war_id: i64, languages: impl IntoIterator<Item = Language>) -> Result<Translations, HelldiversError> {
+    let mut requests = tokio::task::JoinSet::new();
+    for language in languages {
+        requests.spawn(async move {
+            let (status, news_feed) = tokio::join!(get_status(war_id, language), get_news_feed(war_id, language));
+            (language, status.and_then(|status| Ok((status, news_feed?))))
+        });
+    }
+
+    let mut responses = Vec::new();
+    let mut errors = HashMap::new();
+    let mut last_error = None;
+    while let Some(response) = requests.join_next().await {
+        let (language, response) = response.map_err(|e| HelldiversError::APIError(format!("Request task failed: {}", e)))?;
+        match response {
+            Ok((status, news_feed)) => responses.push((language, status, news_feed)),
+            Err(e) => {
+                errors.insert(language, e.to_string());
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if responses.is_empty() => Err(e),
+        _ => Ok(Translations { errors, ..collect_translations(responses) }),
+    }
+}
+
+/// Match up the news items and global events of each language by ID
+pub(crate) fn collect_translations(responses: Vec<(Language, Status, Vec<NewsItem>)>) -> Translations {
+    let mut translations = Translations::default();
+    for (language, status, news_feed) in responses {
+        for item in news_feed {
+            let entry = translations.news.entry(item.id).or_default();
+            entry.id = item.id;
+            entry.published = item.published;
+            entry.messages.insert(language, item.message);
+        }
+        for event in status.global_events {
+            let entry = translations.global_events.entry(event.event_id).or_default();
+            entry.event_id = event.event_id;
+            entry.titles.insert(language, event.title);
+            entry.messages.insert(language, event.message);
+        }
+    }
+    translations
+}
@@ -0,0 +1,126 @@
+use crate::{registry, Registry};
+
+/// The minimum score for `find_planet` and `find_sector` to accept a match
+pub const MIN_MATCH_SCORE: f64 = 0.6;
+
+/// A candidate returned by a name search
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameMatch {
+    pub id: i64,
+    pub name: String,
+    /// How closely the name matches the query, from 0.0 to 1.0 for an exact match
+    pub score: f64,
+}
+
+/// Normalise a name for comparison
+///
+/// Lowercases the name, drops apostrophes and replaces any other punctuation with spaces,
+/// so "Widow's Harbor" and "widows-harbor" both become "widows harbor".
+pub fn normalize_name(name: &str) -> String {
+    let cleaned: String = name.chars()
+        .filter(|c| !matches!(c, '\'' | '’' | '`'))
+        .map(|c| if c.is_alphanumeric() { c.to_lowercase().next().unwrap_or(c) } else { ' ' })
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Search the planets by name, best matches first
+///
+/// Arguments:
+///   query: &str - The name to search for, ignoring case, punctuation and apostrophes
+///   limit: usize - The maximum number of candidates to return
+pub fn find_planets(query: &str, limit: usize) -> Vec<NameMatch> {
+    rank(query, registry().planets.values().map(|p| (p.id, p.name.as_str())), limit)
+}
+
+/// Search the sectors by name, best matches first
+///
+/// Arguments:
+///   query: &str - The name to search for, ignoring case, punctuation and apostrophes
+///   limit: usize - The maximum number of candidates to return
+pub fn find_sectors(query: &str, limit: usize) -> Vec<NameMatch> {
+    rank_sectors(&registry(), query, limit)
+}
+
+/// Search the sector names of a registry, so names sharing an ID are all found
+pub(crate) fn rank_sectors(registry: &Registry, query: &str, limit: usize) -> Vec<NameMatch> {
+    rank(query, registry.sector_names().iter().map(|(id, name)| (*id, name.as_str())), limit)
+}
+
+/// Resolve user input to a planet ID
+///
+/// Accepts either a planet index or a name, returning the best match scoring at least `MIN_MATCH_SCORE`.
+///
+/// Arguments:
+///   query: &str - The index or name of the planet
+pub fn find_planet(query: &str) -> Option<i64> {
+    if let Ok(id) = query.trim().parse::<i64>() {
+        return registry().planets.contains_key(&id).then_some(id);
+    }
+    best_match(find_planets(query, 1))
+}
+
+/// Resolve user input to a sector ID
+///
+/// Accepts either a sector ID or a name, returning the best match scoring at least `MIN_MATCH_SCORE`.
+///
+/// Arguments:
+///   query: &str - The ID or name of the sector
+pub fn find_sector(query: &str) -> Option<i64> {
+    if let Ok(id) = query.trim().parse::<i64>() {
+        return registry().sectors.contains_key(&id).then_some(id);
+    }
+    best_match(find_sectors(query, 1))
+}
+
+fn best_match(matches: Vec<NameMatch>) -> Option<i64> {
+    matches.into_iter().next().filter(|m| m.score >= MIN_MATCH_SCORE).map(|m| m.id)
+}
+
+fn rank<'a>(query: &str, candidates: impl Iterator<Item = (i64, &'a str)>, limit: usize) -> Vec<NameMatch> {
+    let query = normalize_name(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<NameMatch> = candidates
+        .map(|(id, name)| NameMatch { id, name: name.to_string(), score: score(&query, &normalize_name(name)) })
+        .filter(|m| m.score > 0.0)
+        .collect();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+    matches.truncate(limit);
+    matches
+}
+
+/// Score a normalised name against a normalised query
+fn score(query: &str, name: &str) -> f64 {
+    if query == name {
+        return 1.0;
+    }
+    let query_len = query.chars().count();
+    let name_len = name.chars().count();
+    let similarity = 1.0 - levenshtein(query, name) as f64 / query_len.max(name_len) as f64;
+
+    // partial names such as "malevelon" should still rank above unrelated names of similar length
+    if name.contains(query) {
+        similarity.max(0.7 + 0.25 * query_len as f64 / name_len as f64)
+    } else {
+        similarity.max(0.0)
+    }
+}
+
+/// The number of single character edits needed to turn `a` into `b`
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,564 @@
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use futures_util::future::{BoxFuture, FutureExt};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, Mutex};
+
+use crate::error::HelldiversError;
+use crate::models::Language;
+use crate::watch::{StatusWatcher, WarChange};
+use crate::{atom_feed, current_war_id, fetch_snapshot, get_campaigns, rss_feed, FeedConfig, GlobalEventDates, WarClock, WarSnapshot};
+
+/// How the server caches and rate limits requests to the API
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// How long a fetched snapshot is served before the API is asked again
+    pub cache_ttl: Duration,
+    /// How long an expired snapshot is kept, to serve when the API fails or the rate limit is reached
+    pub stale_ttl: Duration,
+    /// The most snapshots fetched from the API per minute, across all wars and languages
+    pub max_upstream_per_minute: usize,
+    /// The most requests per minute from one client address
+    pub max_requests_per_client_per_minute: usize,
+    /// The number of war seasons before the current one that can be requested, older and unknown wars are not found
+    pub past_wars: i64,
+    /// The time between polls of the current war for `/events`, or `None` to not push events
+    pub watch_interval: Option<Duration>,
+    /// The language of the names and text in events
+    pub watch_language: Language,
+    /// Called with the error of each failed poll of the current war, e.g. to log it
+    pub on_watch_error: Option<fn(&HelldiversError)>,
+    /// The number of recent events kept for clients resuming after a reconnect
+    pub event_history: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            cache_ttl: Duration::from_secs(20),
+            stale_ttl: Duration::from_secs(600),
+            max_upstream_per_minute: 30,
+            max_requests_per_client_per_minute: 120,
+            past_wars: 5,
+            watch_interval: Some(Duration::from_secs(60)),
+            watch_language: Language::English,
+            on_watch_error: None,
+            event_history: 1000,
+        }
+    }
+}
+
+/// A sliding window limit on the number of events per period
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: usize,
+    period: Duration,
+    events: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, period: Duration) -> RateLimiter {
+        RateLimiter { limit, period, events: VecDeque::new() }
+    }
+
+    /// Record an event at `now` if the limit allows it, returning whether it was allowed
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        while self.events.front().is_some_and(|event| now.saturating_duration_since(*event) >= self.period) {
+            self.events.pop_front();
+        }
+        if self.events.len() >= self.limit {
+            return false;
+        }
+        self.events.push_back(now);
+        true
+    }
+
+    /// Whether every recorded event is older than the period at `now`
+    pub fn is_idle(&self, now: Instant) -> bool {
+        self.events.back().is_none_or(|event| now.saturating_duration_since(*event) >= self.period)
+    }
+}
+
+/// Where the server gets the current war and snapshots from, the API unless replaced in tests
+#[derive(Clone, Copy)]
+pub(crate) struct Upstream {
+    pub(crate) current_war_id: fn() -> BoxFuture<'static, Result<i64, HelldiversError>>,
+    pub(crate) fetch_snapshot: fn(i64, Language) -> BoxFuture<'static, Result<WarSnapshot, HelldiversError>>,
+}
+
+impl Default for Upstream {
+    fn default() -> Self {
+        Upstream {
+            current_war_id: || current_war_id().boxed(),
+            fetch_snapshot: |war_id, language| fetch_snapshot(war_id, language).boxed(),
+        }
+    }
+}
+
+struct CachedSnapshot {
+    snapshot: Arc<WarSnapshot>,
+    fetched: Instant,
+}
+
+/// A snapshot slot per war and language, locked while it is being fetched so concurrent requests share one fetch
+type Slot = Arc<Mutex<Option<CachedSnapshot>>>;
+
+/// The clock and global event dates of the current war's feeds, kept so entries keep their dates between fetches
+struct FeedDates {
+    war_id: i64,
+    clock: WarClock,
+    events: GlobalEventDates,
+}
+
+struct AppState {
+    config: ServerConfig,
+    upstream: Upstream,
+    events: Arc<EventHub>,
+    slots: Mutex<HashMap<(i64, Language), Slot>>,
+    limiter: Mutex<RateLimiter>,
+    clients: Mutex<HashMap<IpAddr, RateLimiter>>,
+    feed_dates: std::sync::Mutex<Option<FeedDates>>,
+}
+
+impl AppState {
+    async fn current_war_id(&self) -> Result<i64, ApiError> {
+        Ok((self.upstream.current_war_id)().await?)
+    }
+
+    /// Get a cached snapshot, fetching a new one once the cached one expires
+    ///
+    /// Only the current war and the `config.past_wars` before it are served. When the rate limit is reached or the
+    /// API fails, an expired snapshot is served rather than an error, until it is older than `config.stale_ttl`.
+    async fn snapshot(&self, war_id: i64, language: Language) -> Result<Arc<WarSnapshot>, ApiError> {
+        let current = self.current_war_id().await?;
+        if war_id > current || war_id < current - self.config.past_wars {
+            return Err(ApiError(StatusCode::NOT_FOUND, format!("Unknown war: {}", war_id)));
+        }
+        let slot = {
+            let mut slots = self.slots.lock().await;
+            slots.retain(|_, slot| match slot.try_lock() {
+                Ok(cached) => cached.as_ref().is_some_and(|entry| entry.fetched.elapsed() < self.config.stale_ttl),
+                // the slot is being fetched
+                Err(_) => true,
+            });
+            slots.entry((war_id, language)).or_default().clone()
+        };
+        let mut cached = slot.lock().await;
+        if let Some(entry) = cached.as_ref().filter(|entry| entry.fetched.elapsed() < self.config.cache_ttl) {
+            return Ok(entry.snapshot.clone());
+        }
+        if !self.limiter.lock().await.try_acquire(Instant::now()) {
+            return match cached.as_ref() {
+                Some(entry) => Ok(entry.snapshot.clone()),
+                None => Err(ApiError(StatusCode::TOO_MANY_REQUESTS, "Too many requests to the API, try again later".to_string())),
+            };
+        }
+        match (self.upstream.fetch_snapshot)(war_id, language).await {
+            Ok(snapshot) => {
+                let snapshot = Arc::new(snapshot);
+                *cached = Some(CachedSnapshot { snapshot: snapshot.clone(), fetched: Instant::now() });
+                Ok(snapshot)
+            }
+            Err(e) => match cached.as_ref() {
+                Some(entry) => Ok(entry.snapshot.clone()),
+                None => Err(ApiError::from(e)),
+            },
+        }
+    }
+
+    /// Record a request from `client`, returning whether its rate limit allows it
+    async fn admit(&self, client: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut clients = self.clients.lock().await;
+        if !clients.contains_key(&client) {
+            // only clients with requests in the current window are kept
+            clients.retain(|_, limiter| !limiter.is_idle(now));
+        }
+        clients.entry(client)
+            .or_insert_with(|| RateLimiter::new(self.config.max_requests_per_client_per_minute, Duration::from_secs(60)))
+            .try_acquire(now)
+    }
+}
+
+/// Reject requests over the rate limit of the client address they came from
+///
+/// Routers served without `ConnectInfo`, e.g. embedded without `into_make_service_with_connect_info`, are not
+/// limited per client.
+async fn limit_clients(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    if let Some(ConnectInfo(addr)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        if !state.admit(addr.ip()).await {
+            return ApiError(StatusCode::TOO_MANY_REQUESTS, "Too many requests, try again later".to_string()).into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// A change pushed to clients, numbered so clients can resume after reconnecting
+///
+/// IDs continue from the Unix time in milliseconds when the server started, so they keep increasing across restarts.
+#[derive(Debug, Clone, Serialize)]
+pub struct WarEvent {
+    pub id: u64,
+    pub war_id: i64,
+    #[serde(flatten)]
+    pub change: WarChange,
+}
+
+/// Numbers changes and passes them to subscribers, keeping the latest for replay
+pub struct EventHub {
+    sender: broadcast::Sender<Arc<WarEvent>>,
+    history: std::sync::Mutex<VecDeque<Arc<WarEvent>>>,
+    capacity: usize,
+    next_id: AtomicU64,
+}
+
+/// New events for a subscriber, after the kept events it missed
+pub struct Subscription {
+    /// Set when events after `last_event_id` are no longer kept, to the ID the client resumes from instead
+    pub reset: Option<u64>,
+    pub replay: Vec<Arc<WarEvent>>,
+    pub receiver: broadcast::Receiver<Arc<WarEvent>>,
+}
+
+impl EventHub {
+    pub fn new(capacity: usize) -> EventHub {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        let first_id = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(1);
+        EventHub { sender, history: std::sync::Mutex::new(VecDeque::new()), capacity, next_id: AtomicU64::new(first_id) }
+    }
+
+    /// Number a change and send it to every subscriber
+    pub fn publish(&self, war_id: i64, change: WarChange) -> Arc<WarEvent> {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let event = Arc::new(WarEvent { id: self.next_id.fetch_add(1, Ordering::Relaxed), war_id, change });
+        history.push_back(event.clone());
+        while history.len() > self.capacity {
+            history.pop_front();
+        }
+        // there may be no subscribers, which is not an error
+        let _ = self.sender.send(event.clone());
+        event
+    }
+
+    /// Subscribe to new events, with the kept events after `last_event_id` to replay first
+    ///
+    /// Without a `last_event_id`, nothing is replayed. If events after it are no longer kept, or it is from a later
+    /// server, the subscription is reset and every kept event is replayed.
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        // holding the history lock while subscribing means no event is missed or sent twice
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let next_id = self.next_id.load(Ordering::Relaxed);
+        let first = history.front().map_or(next_id, |event| event.id);
+        let (reset, replay) = match last_event_id {
+            Some(last) if last.saturating_add(1) < first || last >= next_id => (Some(first - 1), history.iter().cloned().collect()),
+            Some(last) => (None, history.iter().filter(|event| event.id > last).cloned().collect()),
+            None => (None, Vec::new()),
+        };
+        Subscription { reset, replay, receiver: self.sender.subscribe() }
+    }
+}
+
+/// Poll the current war and publish its changes, starting over when the war season changes
+async fn watch(hub: Arc<EventHub>, interval: Duration, language: Language, on_error: Option<fn(&HelldiversError)>) {
+    let report = |e: HelldiversError| {
+        if let Some(on_error) = on_error {
+            on_error(&e);
+        }
+    };
+    let mut interval = tokio::time::interval(interval);
+    let mut watcher: Option<StatusWatcher> = None;
+    loop {
+        interval.tick().await;
+        let war_id = match current_war_id().await {
+            Ok(war_id) => war_id,
+            Err(e) => {
+                report(e);
+                continue;
+            }
+        };
+        let watcher = match &mut watcher {
+            Some(watcher) if watcher.war_id == war_id => watcher,
+            _ => watcher.insert(StatusWatcher::new(war_id, language)),
+        };
+        match watcher.poll().await {
+            Ok(changes) => {
+                for change in changes {
+                    hub.publish(war_id, change);
+                }
+            }
+            Err(e) => report(e),
+        }
+    }
+}
+
+/// An error response, as `{"error": message}`
+struct ApiError(StatusCode, String);
+
+impl From<HelldiversError> for ApiError {
+    fn from(err: HelldiversError) -> Self {
+        let status = match err {
+            HelldiversError::InvalidWarId(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        ApiError(status, err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> Response {
+    Json(value).into_response()
+}
+
+/// The language of the response, from the `Accept-Language` header
+fn language(headers: &HeaderMap) -> Language {
+    headers.get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(Language::from_accept_language)
+        .unwrap_or_default()
+}
+
+/// Build the routes of the server
+///
+/// Every route returns JSON in the language of the `Accept-Language` header:
+///
+/// - `/war/current`: the ID of the current war
+/// - `/war/{id}/status`, `/war/{id}/info`, `/war/{id}/news`: the API responses with names filled in
+/// - `/war/{id}/planets`, `/war/{id}/planets/{index}`: planets with their status, sector, owner and liberation
+/// - `/war/{id}/campaigns`: campaigns with their type, planet and enemy faction
+/// - `/planets`, `/planets/{index}`, `/campaigns`: the same for the current war
+/// - `/feed.atom`, `/feed.rss`: the news and global events of the current war as a feed
+/// - `/events`: server-sent `WarEvent`s, resuming after the `Last-Event-ID` header
+/// - `/events/ws`: the same over a WebSocket, resuming after the `last_event_id` query parameter
+///
+/// If `config.watch_interval` is set, a task polling the current war is spawned, so this must be called
+/// from within a Tokio runtime.
+pub fn router(config: ServerConfig) -> Router {
+    router_with(config, Upstream::default())
+}
+
+pub(crate) fn router_with(config: ServerConfig, upstream: Upstream) -> Router {
+    let limiter = RateLimiter::new(config.max_upstream_per_minute, Duration::from_secs(60));
+    let events = Arc::new(EventHub::new(config.event_history));
+    if let Some(interval) = config.watch_interval {
+        tokio::spawn(watch(events.clone(), interval, config.watch_language, config.on_watch_error));
+    }
+    let state = Arc::new(AppState {
+        config,
+        upstream,
+        events,
+        slots: Mutex::new(HashMap::new()),
+        limiter: Mutex::new(limiter),
+        clients: Mutex::new(HashMap::new()),
+        feed_dates: std::sync::Mutex::new(None),
+    });
+    Router::new()
+        .route("/war/current", get(current_war))
+        .route("/war/:id/status", get(status))
+        .route("/war/:id/info", get(info))
+        .route("/war/:id/news", get(news))
+        .route("/war/:id/planets", get(planets))
+        .route("/war/:id/planets/:index", get(planet))
+        .route("/war/:id/campaigns", get(campaigns))
+        .route("/planets", get(current_planets))
+        .route("/planets/:index", get(current_planet))
+        .route("/campaigns", get(current_campaigns))
+        .route("/feed.atom", get(atom))
+        .route("/feed.rss", get(rss))
+        .route("/events", get(events_sse))
+        .route("/events/ws", get(events_ws))
+        .layer(middleware::from_fn_with_state(state.clone(), limit_clients))
+        .with_state(state)
+}
+
+/// Serve the API until the server fails
+///
+/// Arguments:
+///   addr: SocketAddr - The address to listen on, e.g. `127.0.0.1:8080`
+///   config: ServerConfig - The cache lifetime and upstream rate limit
+pub async fn serve(addr: SocketAddr, config: ServerConfig) -> Result<(), HelldiversError> {
+    let listener = tokio::net::TcpListener::bind(addr).await
+        .map_err(|e| HelldiversError::ServerError(format!("{}: {}", addr, e)))?;
+    serve_router(listener, router(config)).await
+}
+
+/// Serve routes with the client addresses the per-client rate limit needs
+pub(crate) async fn serve_router(listener: tokio::net::TcpListener, router: Router) -> Result<(), HelldiversError> {
+    axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>()).await
+        .map_err(|e| HelldiversError::ServerError(e.to_string()))
+}
+
+type AppResult = Result<Response, ApiError>;
+
+async fn current_war(State(state): State<Arc<AppState>>) -> AppResult {
+    Ok(json(&serde_json::json!({ "id": state.current_war_id().await? })))
+}
+
+async fn status(State(state): State<Arc<AppState>>, Path(id): Path<i64>, headers: HeaderMap) -> AppResult {
+    Ok(json(&state.snapshot(id, language(&headers)).await?.status))
+}
+
+async fn info(State(state): State<Arc<AppState>>, Path(id): Path<i64>, headers: HeaderMap) -> AppResult {
+    Ok(json(&state.snapshot(id, language(&headers)).await?.war_info))
+}
+
+async fn news(State(state): State<Arc<AppState>>, Path(id): Path<i64>, headers: HeaderMap) -> AppResult {
+    Ok(json(&state.snapshot(id, language(&headers)).await?.news))
+}
+
+async fn planets(State(state): State<Arc<AppState>>, Path(id): Path<i64>, headers: HeaderMap) -> AppResult {
+    Ok(json(&state.snapshot(id, language(&headers)).await?.planets()))
+}
+
+async fn planet(State(state): State<Arc<AppState>>, Path((id, index)): Path<(i64, i64)>, headers: HeaderMap) -> AppResult {
+    let snapshot = state.snapshot(id, language(&headers)).await?;
+    let planet = snapshot.planet(index).ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("Unknown planet: {}", index)))?;
+    Ok(json(&planet))
+}
+
+async fn campaigns(State(state): State<Arc<AppState>>, Path(id): Path<i64>, headers: HeaderMap) -> AppResult {
+    let snapshot = state.snapshot(id, language(&headers)).await?;
+    Ok(json(&get_campaigns(&snapshot.war_info, &snapshot.status)))
+}
+
+async fn current_planets(state: State<Arc<AppState>>, headers: HeaderMap) -> AppResult {
+    let war_id = state.current_war_id().await?;
+    planets(state, Path(war_id), headers).await
+}
+
+async fn current_planet(state: State<Arc<AppState>>, Path(index): Path<i64>, headers: HeaderMap) -> AppResult {
+    let war_id = state.current_war_id().await?;
+    planet(state, Path((war_id, index)), headers).await
+}
+
+async fn current_campaigns(state: State<Arc<AppState>>, headers: HeaderMap) -> AppResult {
+    let war_id = state.current_war_id().await?;
+    campaigns(state, Path(war_id), headers).await
+}
+
+/// Generate a feed of the current war, linking to itself through the `Host` header
+async fn feed(state: &AppState, headers: &HeaderMap, path: &str, generate: FeedGenerator) -> AppResult {
+    let language = language(headers);
+    let war_id = state.current_war_id().await?;
+    let snapshot = state.snapshot(war_id, language).await?;
+    let host = headers.get(header::HOST).and_then(|value| value.to_str().ok()).unwrap_or("localhost");
+    let config = FeedConfig { language, ..FeedConfig::new(war_id, &format!("http://{}{}", host, path)) };
+    let (clock, events) = {
+        let mut feed_dates = state.feed_dates.lock().unwrap_or_else(|e| e.into_inner());
+        // the clock is taken from the first snapshot of a war, so dates do not move with the time of each fetch
+        let dates = match &mut *feed_dates {
+            Some(dates) if dates.war_id == war_id => dates,
+            _ => feed_dates.insert(FeedDates {
+                war_id,
+                clock: WarClock::from_war_time(snapshot.time, snapshot.fetched_at as i64),
+                events: GlobalEventDates::default(),
+            }),
+        };
+        dates.events.record(&snapshot.status);
+        (dates.clock, dates.events.clone())
+    };
+    Ok(generate(&snapshot.news, Some(&snapshot.status), &events, &clock, &config).into_response())
+}
+
+type FeedGenerator = fn(&[crate::NewsItem], Option<&crate::Status>, &GlobalEventDates, &WarClock, &FeedConfig) -> String;
+
+async fn atom(State(state): State<Arc<AppState>>, headers: HeaderMap) -> AppResult {
+    let response = feed(&state, &headers, "/feed.atom", atom_feed).await?;
+    Ok(([(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")], response).into_response())
+}
+
+async fn rss(State(state): State<Arc<AppState>>, headers: HeaderMap) -> AppResult {
+    let response = feed(&state, &headers, "/feed.rss", rss_feed).await?;
+    Ok(([(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")], response).into_response())
+}
+
+#[derive(Deserialize)]
+struct ResumeQuery {
+    last_event_id: Option<u64>,
+}
+
+async fn events_sse(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let last_event_id = headers.get("Last-Event-ID")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok());
+    let Subscription { reset, replay, receiver } = state.events.subscribe(last_event_id);
+
+    let reset = reset.map(|id| Event::default().id(id.to_string()).json_data(reset_message(id)).unwrap_or_default());
+    let events = stream::unfold((VecDeque::from(replay), receiver), |(mut replay, mut receiver)| async move {
+        let event = match replay.pop_front() {
+            Some(event) => event,
+            None => next_event(&mut receiver).await?,
+        };
+        let sse = Event::default().id(event.id.to_string()).json_data(&*event).unwrap_or_default();
+        Some((Ok(sse), (replay, receiver)))
+    });
+    let events = stream::iter(reset.map(Ok)).chain(events);
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+async fn events_ws(State(state): State<Arc<AppState>>, Query(query): Query<ResumeQuery>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| push_events(socket, state.events.clone(), query.last_event_id))
+}
+
+async fn push_events(mut socket: WebSocket, events: Arc<EventHub>, last_event_id: Option<u64>) {
+    let Subscription { reset, replay, mut receiver } = events.subscribe(last_event_id);
+    if let Some(id) = reset {
+        if socket.send(Message::Text(reset_message(id).to_string())).await.is_err() {
+            return;
+        }
+    }
+    for event in replay {
+        if send_event(&mut socket, &event).await.is_err() {
+            return;
+        }
+    }
+    loop {
+        tokio::select! {
+            event = next_event(&mut receiver) => match event {
+                Some(event) if send_event(&mut socket, &event).await.is_ok() => {}
+                _ => return,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                // clients have nothing to say, pings are answered by axum
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+async fn send_event(socket: &mut WebSocket, event: &WarEvent) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(event).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+/// Tell a client that events it missed are no longer kept, and that it continues after `id`
+fn reset_message(id: u64) -> serde_json::Value {
+    serde_json::json!({ "id": id, "type": "reset" })
+}
+
+/// The next event for a subscriber, or `None` once it falls too far behind or the hub is dropped
+///
+/// Ending the stream of a client that fell behind makes it reconnect with the ID of the last event it saw, and
+/// replay the rest from the history.
+async fn next_event(receiver: &mut broadcast::Receiver<Arc<WarEvent>>) -> Option<Arc<WarEvent>> {
+    receiver.recv().await.ok()
+}
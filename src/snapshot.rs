@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::error::HelldiversError;
+use crate::export::liberation;
+use crate::models::api::{NewsItem, PlanetInfo, PlanetStatus, Status, WarInfo};
+use crate::models::Language;
+use crate::{get_news_feed, get_status, get_war_info, get_war_time, registry, Registry};
+
+/// An endpoint that failed while taking a snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointError {
+    pub endpoint: &'static str,
+    pub message: String,
+}
+
+/// The status, information, time and news of a war, fetched together
+#[derive(Debug, Serialize)]
+pub struct WarSnapshot {
+    pub war_id: i64,
+    pub language: Language,
+    /// The war time of the snapshot, from the WarTime endpoint or the Status if that failed
+    pub time: i64,
+    /// When the snapshot was taken, in seconds since the Unix epoch
+    pub fetched_at: u64,
+    pub status: Status,
+    pub war_info: WarInfo,
+    pub news: Vec<NewsItem>,
+    /// The optional endpoints that failed, whose data is missing from the snapshot
+    pub errors: Vec<EndpointError>,
+}
+
+/// A planet with its status, information, sector and owner
+#[derive(Debug, Serialize)]
+pub struct PlanetView<'a> {
+    pub index: i64,
+    pub name: String,
+    pub info: &'a PlanetInfo,
+    pub status: Option<&'a PlanetStatus>,
+    pub sector: i64,
+    pub sector_name: Option<String>,
+    pub owner: Option<i64>,
+    pub owner_name: Option<String>,
+    /// 100 for planets owned by humans, otherwise the share of health the planet has lost, in percent
+    pub liberation: Option<f64>,
+}
+
+/// Fetch the status, information, time and news of a war concurrently
+///
+/// The Status and WarInfo are required, and their errors are returned. If the WarTime or NewsFeed endpoints fail,
+/// the snapshot falls back to the Status time and an empty news feed, and records the error in `errors`.
+///
+/// Arguments:
+///   war_id: i64 - The ID of the war
+///   language: Language - The language to get the in-game text and planet names in
+pub async fn fetch_snapshot(war_id: i64, language: Language) -> Result<WarSnapshot, HelldiversError> {
+    let (status, war_info, war_time, news) = tokio::join!(
+        get_status(war_id, language),
+        get_war_info(war_id),
+        get_war_time(war_id),
+        get_news_feed(war_id, language),
+    );
+    let (status, war_info) = (status?, war_info?);
+
+    let mut errors = Vec::new();
+    let time = war_time.unwrap_or_else(|e| {
+        errors.push(EndpointError { endpoint: "WarTime", message: e.to_string() });
+        status.time
+    });
+    let news = news.unwrap_or_else(|e| {
+        errors.push(EndpointError { endpoint: "NewsFeed", message: e.to_string() });
+        Vec::new()
+    });
+    let fetched_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+
+    Ok(WarSnapshot { war_id, language, time, fetched_at, status, war_info, news, errors })
+}
+
+impl WarSnapshot {
+    /// Every planet in the WarInfo, ordered by index
+    pub fn planets(&self) -> Vec<PlanetView<'_>> {
+        let statuses: HashMap<i64, &PlanetStatus> = self.status.planet_status.iter().map(|ps| (ps.index, ps)).collect();
+        let registry = registry();
+        let mut planets: Vec<PlanetView> = self.war_info.planet_infos.iter()
+            .map(|info| self.view(&registry, info, statuses.get(&info.index).copied()))
+            .collect();
+        planets.sort_by_key(|planet| planet.index);
+        planets
+    }
+
+    /// A planet by its index
+    pub fn planet(&self, index: i64) -> Option<PlanetView<'_>> {
+        let info = self.war_info.planet_infos.iter().find(|info| info.index == index)?;
+        let status = self.status.planet_status.iter().find(|ps| ps.index == index);
+        Some(self.view(&registry(), info, status))
+    }
+
+    /// Whether every endpoint responded
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn view<'a>(&'a self, registry: &Registry, info: &'a PlanetInfo, status: Option<&'a PlanetStatus>) -> PlanetView<'a> {
+        let owner = status.map(|ps| ps.owner);
+        PlanetView {
+            index: info.index,
+            name: registry.localized_planet_name(info.index, self.language).unwrap_or_default(),
+            info,
+            status,
+            sector: info.sector,
+            sector_name: registry.localized_sector_name(info.sector, self.language),
+            owner,
+            owner_name: owner.and_then(|owner| registry.localized_faction_name(owner, self.language)),
+            liberation: status.and_then(|ps| liberation(ps.owner, ps.health, Some(info.max_health))),
+        }
+    }
+}
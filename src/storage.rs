@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::error::HelldiversError;
+use crate::export::{liberation, PlanetRow};
+use crate::models::api::{NewsItem, PlanetInfo, Status, WarInfo};
+use crate::models::Language;
+use crate::{registry, WarSnapshot};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY,
+    war_id INTEGER NOT NULL,
+    war_time INTEGER NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    impact_multiplier REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS snapshots_war ON snapshots (war_id, recorded_at);
+
+CREATE TABLE IF NOT EXISTS planet_status (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    planet_index INTEGER NOT NULL,
+    owner INTEGER NOT NULL,
+    health INTEGER NOT NULL,
+    regen_per_second REAL NOT NULL,
+    players INTEGER NOT NULL,
+    PRIMARY KEY (snapshot_id, planet_index)
+);
+CREATE INDEX IF NOT EXISTS planet_status_planet ON planet_status (planet_index, snapshot_id);
+
+CREATE TABLE IF NOT EXISTS campaigns (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    campaign_id INTEGER NOT NULL,
+    planet_index INTEGER NOT NULL,
+    type INTEGER NOT NULL,
+    count INTEGER NOT NULL,
+    PRIMARY KEY (snapshot_id, campaign_id)
+);
+
+CREATE TABLE IF NOT EXISTS planet_attacks (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id) ON DELETE CASCADE,
+    source INTEGER NOT NULL,
+    target INTEGER NOT NULL,
+    PRIMARY KEY (snapshot_id, source, target)
+);
+
+CREATE TABLE IF NOT EXISTS global_events (
+    war_id INTEGER NOT NULL,
+    event_id INTEGER NOT NULL,
+    language TEXT NOT NULL,
+    title TEXT NOT NULL,
+    message TEXT NOT NULL,
+    race INTEGER NOT NULL,
+    first_seen INTEGER NOT NULL,
+    PRIMARY KEY (war_id, event_id, language)
+);
+
+CREATE TABLE IF NOT EXISTS news (
+    war_id INTEGER NOT NULL,
+    id INTEGER NOT NULL,
+    language TEXT NOT NULL,
+    published INTEGER NOT NULL,
+    type INTEGER NOT NULL,
+    tag_ids TEXT NOT NULL,
+    message TEXT NOT NULL,
+    PRIMARY KEY (war_id, id, language)
+);
+";
+
+/// A planet's status at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanetSample {
+    /// When the snapshot was recorded, in seconds since the Unix epoch
+    pub recorded_at: u64,
+    pub war_time: i64,
+    pub owner: i64,
+    pub health: i64,
+    pub regen_per_second: f64,
+    pub players: i64,
+}
+
+/// A change of a planet's owner
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OwnerChange {
+    /// When the new owner was first recorded, in seconds since the Unix epoch
+    pub recorded_at: u64,
+    pub war_time: i64,
+    pub owner: i64,
+}
+
+/// A global event as first recorded
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoredGlobalEvent {
+    pub event_id: i64,
+    pub title: String,
+    pub message: String,
+    pub race: i64,
+    /// When the event was first recorded, in seconds since the Unix epoch
+    pub first_seen: u64,
+}
+
+/// A SQLite database of war snapshots, news and global events
+///
+/// Every recorded Status becomes a snapshot, with the planet status, campaigns and attacks in their own tables.
+/// News items and global events are stored once per language.
+pub struct HistoryStore {
+    connection: Connection,
+}
+
+impl HistoryStore {
+    /// Open or create a database file
+    ///
+    /// Arguments:
+    ///   path: impl AsRef<Path> - The path of the database
+    pub fn open(path: impl AsRef<Path>) -> Result<HistoryStore, HelldiversError> {
+        HistoryStore::with_connection(Connection::open(path)?)
+    }
+
+    /// Create a database in memory, which is lost when the store is dropped
+    pub fn open_in_memory() -> Result<HistoryStore, HelldiversError> {
+        HistoryStore::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<HistoryStore, HelldiversError> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(HistoryStore { connection })
+    }
+
+    /// Record a Status and its global events, returning the ID of the snapshot
+    ///
+    /// Arguments:
+    ///   status: &Status - The Status to record
+    ///   language: Language - The language the Status was fetched in
+    ///   recorded_at: u64 - When the Status was fetched, in seconds since the Unix epoch
+    pub fn record_status(&mut self, status: &Status, language: Language, recorded_at: u64) -> Result<i64, HelldiversError> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO snapshots (war_id, war_time, recorded_at, impact_multiplier) VALUES (?1, ?2, ?3, ?4)",
+            params![status.war_id, status.time, recorded_at, status.impact_multiplier],
+        )?;
+        let snapshot_id = transaction.last_insert_rowid();
+        {
+            let mut insert = transaction.prepare(
+                "INSERT INTO planet_status (snapshot_id, planet_index, owner, health, regen_per_second, players)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for ps in &status.planet_status {
+                insert.execute(params![snapshot_id, ps.index, ps.owner, ps.health, ps.regen_per_second, ps.players])?;
+            }
+            let mut insert = transaction.prepare(
+                "INSERT OR IGNORE INTO campaigns (snapshot_id, campaign_id, planet_index, type, count) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            for campaign in &status.campaigns {
+                insert.execute(params![snapshot_id, campaign.id, campaign.planet_index, campaign.r#type, campaign.count])?;
+            }
+            let mut insert = transaction.prepare(
+                "INSERT OR IGNORE INTO planet_attacks (snapshot_id, source, target) VALUES (?1, ?2, ?3)",
+            )?;
+            for attack in &status.planet_attacks {
+                insert.execute(params![snapshot_id, attack.source, attack.target])?;
+            }
+            let mut insert = transaction.prepare(
+                "INSERT OR IGNORE INTO global_events (war_id, event_id, language, title, message, race, first_seen)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for event in &status.global_events {
+                insert.execute(params![
+                    status.war_id, event.event_id, language.to_str(), event.title, event.message, event.race, recorded_at
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(snapshot_id)
+    }
+
+    /// Record news items, skipping items already stored, and return the number of new items
+    ///
+    /// Arguments:
+    ///   war_id: i64 - The ID of the war the news belongs to
+    ///   language: Language - The language the news was fetched in
+    ///   items: &[NewsItem] - The news items to record
+    pub fn record_news(&mut self, war_id: i64, language: Language, items: &[NewsItem]) -> Result<usize, HelldiversError> {
+        let transaction = self.connection.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut insert = transaction.prepare(
+                "INSERT OR IGNORE INTO news (war_id, id, language, published, type, tag_ids, message)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for item in items {
+                let tag_ids = serde_json::to_string(&item.tag_ids)?;
+                inserted += insert.execute(params![
+                    war_id, item.id, language.to_str(), item.published, item.news_type, tag_ids, item.message
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(inserted)
+    }
+
+    /// Record the Status, global events and news of a snapshot, returning the ID of the snapshot
+    pub fn record_snapshot(&mut self, snapshot: &WarSnapshot) -> Result<i64, HelldiversError> {
+        let snapshot_id = self.record_status(&snapshot.status, snapshot.language, snapshot.fetched_at)?;
+        self.record_news(snapshot.war_id, snapshot.language, &snapshot.news)?;
+        Ok(snapshot_id)
+    }
+
+    /// Get the recorded status of a planet, oldest first
+    ///
+    /// For the last 24 hours, pass the current Unix time minus 86400 as `since`.
+    ///
+    /// Arguments:
+    ///   war_id: i64 - The ID of the war
+    ///   planet_index: i64 - The index of the planet
+    ///   since: u64 - The earliest recording time to include, in seconds since the Unix epoch
+    pub fn planet_history(&self, war_id: i64, planet_index: i64, since: u64) -> Result<Vec<PlanetSample>, HelldiversError> {
+        let mut query = self.connection.prepare(
+            "SELECT s.recorded_at, s.war_time, p.owner, p.health, p.regen_per_second, p.players
+             FROM planet_status p JOIN snapshots s ON s.id = p.snapshot_id
+             WHERE s.war_id = ?1 AND p.planet_index = ?2 AND s.recorded_at >= ?3
+             ORDER BY s.recorded_at, s.id",
+        )?;
+        let samples = query.query_map(params![war_id, planet_index, since], |row| {
+            Ok(PlanetSample {
+                recorded_at: row.get(0)?,
+                war_time: row.get(1)?,
+                owner: row.get(2)?,
+                health: row.get(3)?,
+                regen_per_second: row.get(4)?,
+                players: row.get(5)?,
+            })
+        })?;
+        Ok(samples.collect::<Result<_, _>>()?)
+    }
+
+    /// Get every recorded planet status of a war as export rows, oldest first
+    ///
+    /// Arguments:
+    ///   war_info: &WarInfo - The WarInfo of the war, for the sectors and maximum planet health
+    ///   since: u64 - The earliest recording time to include, in seconds since the Unix epoch
+    pub fn planet_rows(&self, war_info: &WarInfo, since: u64) -> Result<Vec<PlanetRow>, HelldiversError> {
+        let infos: HashMap<i64, &PlanetInfo> = war_info.planet_infos.iter().map(|pi| (pi.index, pi)).collect();
+        let registry = registry();
+        let mut query = self.connection.prepare(
+            "SELECT s.war_time, s.recorded_at, p.planet_index, p.owner, p.health, p.regen_per_second, p.players
+             FROM planet_status p JOIN snapshots s ON s.id = p.snapshot_id
+             WHERE s.war_id = ?1 AND s.recorded_at >= ?2
+             ORDER BY s.recorded_at, s.id, p.planet_index",
+        )?;
+        let rows = query.query_map(params![war_info.war_id, since], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?))
+        })?;
+        let mut planet_rows = Vec::new();
+        for row in rows {
+            let (war_time, recorded_at, planet_index, owner, health, regen_per_second, players): (i64, u64, i64, i64, i64, f64, i64) = row?;
+            let info = infos.get(&planet_index);
+            let max_health = info.map(|pi| pi.max_health);
+            planet_rows.push(PlanetRow {
+                war_id: war_info.war_id,
+                war_time,
+                recorded_at: Some(recorded_at),
+                planet_index,
+                planet_name: registry.planet_name(planet_index).unwrap_or_default(),
+                sector: info.map(|pi| pi.sector),
+                owner,
+                owner_name: registry.faction_name(owner).unwrap_or_default(),
+                health,
+                max_health,
+                liberation: liberation(owner, health, max_health),
+                players,
+                regen_per_second,
+            });
+        }
+        Ok(planet_rows)
+    }
+
+    /// Get the owners a planet has had, oldest first, with the first recording of each owner
+    ///
+    /// Arguments:
+    ///   war_id: i64 - The ID of the war
+    ///   planet_index: i64 - The index of the planet
+    pub fn owner_history(&self, war_id: i64, planet_index: i64) -> Result<Vec<OwnerChange>, HelldiversError> {
+        let mut changes: Vec<OwnerChange> = Vec::new();
+        for sample in self.planet_history(war_id, planet_index, 0)? {
+            if changes.last().is_none_or(|change| change.owner != sample.owner) {
+                changes.push(OwnerChange { recorded_at: sample.recorded_at, war_time: sample.war_time, owner: sample.owner });
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Get the recorded news published after a war time, oldest first
+    ///
+    /// Arguments:
+    ///   war_id: i64 - The ID of the war
+    ///   language: Language - The language of the news
+    ///   since: i64 - Only include items published after this war time
+    pub fn news(&self, war_id: i64, language: Language, since: i64) -> Result<Vec<NewsItem>, HelldiversError> {
+        let mut query = self.connection.prepare(
+            "SELECT id, published, type, tag_ids, message FROM news
+             WHERE war_id = ?1 AND language = ?2 AND published > ?3
+             ORDER BY published, id",
+        )?;
+        let rows = query.query_map(params![war_id, language.to_str(), since], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?, row.get(4)?))
+        })?;
+        let mut items = Vec::new();
+        for row in rows {
+            let (id, published, news_type, tag_ids, message) = row?;
+            items.push(NewsItem { id, published, news_type, tag_ids: serde_json::from_str(&tag_ids)?, message, unknown: HashMap::new() });
+        }
+        Ok(items)
+    }
+
+    /// Get the recorded global events of a war, in the order they were first seen
+    ///
+    /// Arguments:
+    ///   war_id: i64 - The ID of the war
+    ///   language: Language - The language of the events
+    pub fn global_events(&self, war_id: i64, language: Language) -> Result<Vec<StoredGlobalEvent>, HelldiversError> {
+        let mut query = self.connection.prepare(
+            "SELECT event_id, title, message, race, first_seen FROM global_events
+             WHERE war_id = ?1 AND language = ?2
+             ORDER BY first_seen, event_id",
+        )?;
+        let events = query.query_map(params![war_id, language.to_str()], |row| {
+            Ok(StoredGlobalEvent {
+                event_id: row.get(0)?,
+                title: row.get(1)?,
+                message: row.get(2)?,
+                race: row.get(3)?,
+                first_seen: row.get(4)?,
+            })
+        })?;
+        Ok(events.collect::<Result<_, _>>()?)
+    }
+
+    /// The recording time of the latest snapshot of a war, in seconds since the Unix epoch
+    pub fn latest_snapshot(&self, war_id: i64) -> Result<Option<u64>, HelldiversError> {
+        Ok(self.connection
+            .query_row("SELECT MAX(recorded_at) FROM snapshots WHERE war_id = ?1", params![war_id], |row| row.get(0))
+            .optional()?
+            .flatten())
+    }
+
+    /// Delete the snapshots recorded before a time, keeping news and global events
+    ///
+    /// Arguments:
+    ///   before: u64 - The recording time to keep snapshots from, in seconds since the Unix epoch
+    pub fn prune(&mut self, before: u64) -> Result<usize, HelldiversError> {
+        Ok(self.connection.execute("DELETE FROM snapshots WHERE recorded_at < ?1", params![before])?)
+    }
+}
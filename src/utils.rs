@@ -1,6 +1,6 @@
-use std::collections::HashMap;
-use crate::{PlanetStatus, Status, WarInfo, get_sector_name, Faction, get_faction_name};
-use crate::models::Sector;
+use std::collections::{HashMap, HashSet};
+use crate::{PlanetStatus, PlanetInfo, Status, WarInfo, Faction, registry};
+use crate::models::{Sector, SectorSummary, FrontPlanet, CampaignView, HUMAN_FACTION_ID};
 
 /// Get the total player count for a status
 ///
@@ -15,6 +15,7 @@ pub fn get_total_player_count(status: &Status) -> i64 {
 /// Arguments:
 ///     status: &Status - The status to get the top planets from
 ///     count: usize - The number of top planets to get
+#[allow(clippy::unnecessary_sort_by)]
 pub fn get_top_planets_by_player_count(status: &Status, count: usize) -> Vec<(&PlanetStatus, i64)> {
     let mut planet_players: Vec<(&PlanetStatus, i64)> = status.planet_status.iter().map(|ps| (ps, ps.players)).collect();
     planet_players.sort_by(|a, b| b.1.cmp(&a.1));
@@ -42,8 +43,10 @@ pub fn get_sectors(status: &WarInfo) -> Vec<Sector> {
     let mut sectors = status.planet_infos.iter().map(|pi| pi.sector).collect::<Vec<_>>();
     sectors.sort();
     sectors.dedup();
+    let registry = registry();
     sectors.into_iter().map(|s| Sector {
-        id: s, name: get_sector_name(s).unwrap_or_default(), planets: status.planet_infos.iter().filter(|pi| pi.sector == s).map(|pi| pi.index).collect()
+        id: s, name: registry.sector_name(s).unwrap_or_default(), planets: status.planet_infos.iter().filter(|pi| pi.sector == s).map(|pi| pi.index).collect(),
+        names: registry.sectors.get(&s).map(|sector| sector.names.clone()).unwrap_or_default(),
     }).collect()
 }
 
@@ -55,6 +58,131 @@ pub fn get_factions(status: &Status) -> Vec<Faction> {
     let mut factions = status.planet_status.iter().map(|ps| ps.owner).collect::<Vec<_>>();
     factions.sort();
     factions.dedup();
-    factions.into_iter().map(|f| Faction { id: f, name: get_faction_name(f).unwrap_or_default() }).collect()
+    let registry = registry();
+    factions.into_iter().map(|f| Faction {
+        id: f, name: registry.faction_name(f).unwrap_or_default(), names: registry.factions.get(&f).map(|faction| faction.names.clone()).unwrap_or_default(),
+    }).collect()
+}
+
+/// Get the front lines of the war
+///
+/// A front planet is a human-owned planet connected by a waypoint to a planet owned by an enemy faction.
+/// The front planets are grouped by enemy faction, so a planet bordering two factions appears in both groups.
+///
+/// Arguments:
+///     war_info: &WarInfo - The WarInfo to get the waypoints from
+///     status: &Status - The Status to get the planet owners, attacks and campaigns from
+pub fn get_front_lines<'a>(war_info: &'a WarInfo, status: &'a Status) -> HashMap<i64, Vec<FrontPlanet<'a>>> {
+    let infos: HashMap<i64, &PlanetInfo> = war_info.planet_infos.iter().map(|pi| (pi.index, pi)).collect();
+    let owners: HashMap<i64, i64> = status.planet_status.iter().map(|ps| (ps.index, ps.owner)).collect();
+
+    // waypoints are not guaranteed to be listed on both ends of a supply line, so link them both ways
+    let mut neighbours: HashMap<i64, HashSet<i64>> = HashMap::new();
+    for pi in &war_info.planet_infos {
+        for waypoint in &pi.waypoints {
+            neighbours.entry(pi.index).or_default().insert(*waypoint);
+            neighbours.entry(*waypoint).or_default().insert(pi.index);
+        }
+    }
+
+    let mut fronts: HashMap<i64, Vec<FrontPlanet>> = HashMap::new();
+    for ps in status.planet_status.iter().filter(|ps| ps.owner == HUMAN_FACTION_ID) {
+        let Some(info) = infos.get(&ps.index) else { continue };
+        let Some(adjacent) = neighbours.get(&ps.index) else { continue };
+
+        let mut enemies: HashMap<i64, Vec<i64>> = HashMap::new();
+        for neighbour in adjacent {
+            match owners.get(neighbour) {
+                Some(&owner) if owner != HUMAN_FACTION_ID => enemies.entry(owner).or_default().push(*neighbour),
+                _ => {}
+            }
+        }
+
+        for (faction, mut enemy_neighbours) in enemies {
+            enemy_neighbours.sort();
+            let attacks = status.planet_attacks.iter()
+                .filter(|pa| pa.source == ps.index || pa.target == ps.index)
+                .collect();
+            let campaigns = status.campaigns.iter()
+                .filter(|c| c.planet_index == ps.index || enemy_neighbours.contains(&c.planet_index))
+                .collect();
+            fronts.entry(faction).or_default().push(FrontPlanet { status: ps, info, enemy_neighbours, attacks, campaigns });
+        }
+    }
+
+    for planets in fronts.values_mut() {
+        planets.sort_by_key(|fp| fp.status.index);
+    }
+    fronts
 }
 
+/// Get a summary of every sector in a war
+///
+/// Arguments:
+///     war_info: &WarInfo - The WarInfo to get the sectors and maximum planet health from
+///     status: &Status - The Status to get the planet owners, players and campaigns from
+pub fn get_sector_summaries(war_info: &WarInfo, status: &Status) -> Vec<SectorSummary> {
+    let statuses: HashMap<i64, &PlanetStatus> = status.planet_status.iter().map(|ps| (ps.index, ps)).collect();
+    let max_health: HashMap<i64, i64> = war_info.planet_infos.iter().map(|pi| (pi.index, pi.max_health)).collect();
+    let campaign_planets: HashSet<i64> = status.campaigns.iter().map(|c| c.planet_index).collect();
+
+    get_sectors(war_info).into_iter().map(|sector| {
+        let mut owners = HashMap::new();
+        let mut contested_planets = Vec::new();
+        let mut players = 0;
+        for ps in sector.planets.iter().filter_map(|index| statuses.get(index)) {
+            *owners.entry(ps.owner).or_insert(0) += 1;
+            players += ps.players;
+            if campaign_planets.contains(&ps.index) || ps.health < max_health[&ps.index] {
+                contested_planets.push(ps.index);
+            }
+        }
+        let liberated = !owners.is_empty() && owners.keys().all(|owner| *owner == HUMAN_FACTION_ID);
+        SectorSummary { id: sector.id, name: sector.name, planets: sector.planets, owners, contested_planets, players, liberated }
+    }).collect()
+}
+
+/// Get the most active sectors, ranked by player count and then by number of contested planets
+///
+/// Arguments:
+///     war_info: &WarInfo - The WarInfo to get the sectors from
+///     status: &Status - The Status to get the sector activity from
+///     count: usize - The number of top sectors to get
+pub fn get_top_sectors_by_activity(war_info: &WarInfo, status: &Status, count: usize) -> Vec<SectorSummary> {
+    let mut summaries = get_sector_summaries(war_info, status);
+    summaries.sort_by_key(|s| std::cmp::Reverse((s.players, s.contested_planets.len())));
+    summaries.into_iter().take(count).collect()
+}
+
+/// Get the active campaigns joined with their planet, event and enemy faction, ordered by player count
+///
+/// Arguments:
+///     war_info: &WarInfo - The WarInfo to get the planet details from
+///     status: &'a Status - The Status to get the campaigns, planet owners and planet events from
+pub fn get_campaigns<'a>(war_info: &'a WarInfo, status: &'a Status) -> Vec<CampaignView<'a>> {
+    let infos: HashMap<i64, &PlanetInfo> = war_info.planet_infos.iter().map(|pi| (pi.index, pi)).collect();
+    let statuses: HashMap<i64, &PlanetStatus> = status.planet_status.iter().map(|ps| (ps.index, ps)).collect();
+    let registry = registry();
+
+    let mut campaigns: Vec<CampaignView> = status.campaigns.iter().map(|campaign| {
+        let event = status.campaign_event(campaign);
+        let planet_status = statuses.get(&campaign.planet_index).copied();
+        let campaign_type = status.campaign_type(campaign);
+        let owner = planet_status.map(|ps| ps.owner);
+        let race = event.map(|event| event.race).or(owner);
+        CampaignView {
+            campaign,
+            campaign_type,
+            status: planet_status,
+            info: infos.get(&campaign.planet_index).copied(),
+            event,
+            owner,
+            owner_name: owner.and_then(|owner| registry.faction_name(owner)).unwrap_or_default(),
+            race,
+            race_name: race.and_then(|race| registry.faction_name(race)).unwrap_or_default(),
+            sector: infos.get(&campaign.planet_index).map(|pi| pi.sector).or(registry.planet_sector(campaign.planet_index)),
+        }
+    }).collect();
+    campaigns.sort_by_key(|c| (std::cmp::Reverse(c.status.map_or(0, |ps| ps.players)), c.campaign.id));
+    campaigns
+}
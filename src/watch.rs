@@ -0,0 +1,106 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::error::HelldiversError;
+use crate::models::api::{NewsItem, Status};
+use crate::models::{CampaignType, Language, NewsCursor};
+use crate::{get_status, news_since};
+
+/// A change between two polls of a war
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WarChange {
+    PlanetFlipped { planet: i64, name: String, from: i64, to: i64 },
+    CampaignStarted { campaign_id: i64, planet: i64, name: String, campaign_type: CampaignType },
+    CampaignEnded { campaign_id: i64, planet: i64, name: String },
+    GlobalEventStarted { event_id: i64, title: String, message: String },
+    NewsPublished { id: i64, published: i64, message: String },
+}
+
+/// Compare two Status responses of a war, planets first, then campaigns and global events
+///
+/// Arguments:
+///   previous: &Status - The older Status
+///   current: &Status - The newer Status
+pub fn diff_status(previous: &Status, current: &Status) -> Vec<WarChange> {
+    let mut changes = Vec::new();
+
+    let owners: HashMap<i64, i64> = previous.planet_status.iter().map(|ps| (ps.index, ps.owner)).collect();
+    for ps in &current.planet_status {
+        if let Some(&from) = owners.get(&ps.index).filter(|owner| **owner != ps.owner) {
+            changes.push(WarChange::PlanetFlipped { planet: ps.index, name: ps.planet_name.clone(), from, to: ps.owner });
+        }
+    }
+
+    let previous_campaigns: HashSet<i64> = previous.campaigns.iter().map(|c| c.id).collect();
+    let current_campaigns: HashSet<i64> = current.campaigns.iter().map(|c| c.id).collect();
+    for campaign in current.campaigns.iter().filter(|c| !previous_campaigns.contains(&c.id)) {
+        changes.push(WarChange::CampaignStarted {
+            campaign_id: campaign.id,
+            planet: campaign.planet_index,
+            name: campaign.planet_name.clone(),
+            campaign_type: current.campaign_type(campaign),
+        });
+    }
+    for campaign in previous.campaigns.iter().filter(|c| !current_campaigns.contains(&c.id)) {
+        changes.push(WarChange::CampaignEnded { campaign_id: campaign.id, planet: campaign.planet_index, name: campaign.planet_name.clone() });
+    }
+
+    let previous_events: HashSet<i64> = previous.global_events.iter().map(|e| e.event_id).collect();
+    for event in current.global_events.iter().filter(|e| !previous_events.contains(&e.event_id)) {
+        changes.push(WarChange::GlobalEventStarted { event_id: event.event_id, title: event.title.clone(), message: event.message.clone() });
+    }
+    changes
+}
+
+impl From<&NewsItem> for WarChange {
+    fn from(item: &NewsItem) -> Self {
+        WarChange::NewsPublished { id: item.id, published: item.published, message: item.message.clone() }
+    }
+}
+
+/// Polls a war and reports what changed since the previous poll
+///
+/// The first poll only records the state of the war, so existing planets, campaigns and news are not reported.
+pub struct StatusWatcher {
+    pub war_id: i64,
+    pub language: Language,
+    status: Option<Status>,
+    cursor: Option<NewsCursor>,
+}
+
+impl StatusWatcher {
+    pub fn new(war_id: i64, language: Language) -> StatusWatcher {
+        StatusWatcher { war_id, language, status: None, cursor: None }
+    }
+
+    /// Fetch the Status and news, and return the changes since the previous poll
+    ///
+    /// If either request fails, the watcher keeps its previous state and the next poll reports the changes instead.
+    pub async fn poll(&mut self) -> Result<Vec<WarChange>, HelldiversError> {
+        let mut cursor = self.cursor.unwrap_or_default();
+        let (status, news) = tokio::join!(get_status(self.war_id, self.language), news_since(self.war_id, self.language, &mut cursor));
+        let (status, news) = (status?, news?);
+        Ok(self.update(status, news, cursor))
+    }
+
+    /// Record a Status and the news since the previous cursor, returning the changes
+    pub(crate) fn update(&mut self, status: Status, news: Vec<NewsItem>, cursor: NewsCursor) -> Vec<WarChange> {
+        let mut changes = match &self.status {
+            Some(previous) => diff_status(previous, &status),
+            None => Vec::new(),
+        };
+        if self.cursor.is_some() {
+            changes.extend(news.iter().map(WarChange::from));
+        }
+        self.status = Some(status);
+        self.cursor = Some(cursor);
+        changes
+    }
+
+    /// The Status of the latest successful poll
+    pub fn status(&self) -> Option<&Status> {
+        self.status.as_ref()
+    }
+}